@@ -0,0 +1,1133 @@
+use crate::sftp_client::shell_quote;
+use crate::storage::OverwritePolicy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 목록이 너무 길면 앞부분만 보여준다
+pub const MAX_PREVIEW_ENTRIES: usize = 10_000;
+// 미리보기를 위해 내려받을 아카이브의 최대 크기
+pub const ARCHIVE_PREVIEW_LIMIT: u64 = 512 * 1024 * 1024;
+// ZIP 끝의 EOCD 레코드를 찾기 위해 읽는 범위 (레코드 22바이트 + 주석 최대 64KB)
+const ZIP_TAIL_BYTES: u64 = 22 + 0xFFFF;
+// 목록을 위해 읽을 중앙 디렉터리의 최대 크기
+const ZIP_CENTRAL_DIRECTORY_LIMIT: u64 = 64 * 1024 * 1024;
+// 압축 폭탄을 막기 위해 한 번에 풀 수 있는 전체 크기
+pub const EXTRACT_SIZE_LIMIT: u64 = 16 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarBz2,
+}
+
+impl ArchiveFormat {
+    /// compress_file이 만드는 확장자와 흔히 쓰는 줄임 확장자로 형식을 정한다
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar.bz2") || name.ends_with(".tbz2") || name.ends_with(".tbz") {
+            Some(Self::TarBz2)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// compress_file의 format 인자("zip", "tar", "tar.gz", "tar.bz2")
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "zip" => Some(Self::Zip),
+            "tar" => Some(Self::Tar),
+            "tar.gz" => Some(Self::TarGz),
+            "tar.bz2" => Some(Self::TarBz2),
+            _ => None,
+        }
+    }
+}
+
+// 서버에 임시 폴더를 만드는 명령. 이름을 예측할 수 없고 권한이 0700이라 다른 사용자가 끼어들 수 없다.
+pub const SERVER_TEMP_DIR_COMMAND: &str = "mktemp -d /tmp/easyftp_compress_XXXXXXXXXX";
+
+/// mktemp가 출력한 임시 폴더 경로. 예상한 위치와 이름이 아니면 None이다.
+pub fn parse_server_temp_dir(stdout: &[u8]) -> Option<String> {
+    let path = std::str::from_utf8(stdout).ok()?.trim();
+    let name = path.strip_prefix("/tmp/easyftp_compress_")?;
+
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then(|| path.to_string())
+}
+
+/// 원격 파일이나 폴더를 그 상위 폴더에서 서버의 tar/zip으로 압축하는 명령.
+/// 로컬에서 압축할 때와 같이 아카이브 안의 경로가 대상 이름으로 시작한다.
+pub fn server_compress_command(
+    remote_path: &str,
+    format: ArchiveFormat,
+    archive_path: &str,
+) -> Option<String> {
+    let trimmed = remote_path.trim_end_matches('/');
+    let (parent, name) = trimmed.rsplit_once('/')?;
+    let parent = if parent.is_empty() { "/" } else { parent };
+    if name.is_empty() {
+        return None;
+    }
+
+    let compress = match format {
+        // zip은 "--"를 지원하지 않는 버전이 있어 옵션처럼 보이는 이름은 서버에서 압축하지 않는다
+        ArchiveFormat::Zip if name.starts_with('-') => return None,
+        ArchiveFormat::Zip => format!(
+            "zip -qr {} {}",
+            shell_quote(archive_path),
+            shell_quote(name)
+        ),
+        ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarBz2 => {
+            let flags = match format {
+                ArchiveFormat::TarGz => "-czf",
+                ArchiveFormat::TarBz2 => "-cjf",
+                _ => "-cf",
+            };
+            format!(
+                "tar {} {} -- {}",
+                flags,
+                shell_quote(archive_path),
+                shell_quote(name)
+            )
+        }
+    };
+
+    Some(format!("cd {} && {}", shell_quote(parent), compress))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size: u64,
+    pub modified: String,
+    pub is_directory: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveListing {
+    pub entries: Vec<ArchiveEntry>,
+    pub truncated: bool,
+}
+
+/// 압축 해제 스트림을 씌운 tar 리더
+pub fn open_tar(path: &Path, format: ArchiveFormat) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let file = File::open(path).map_err(|e| format!("파일 열기 실패: {}", e))?;
+
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::TarGz => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(bzip2::read::MultiBzDecoder::new(file)),
+        _ => Box::new(file),
+    };
+
+    Ok(tar::Archive::new(reader))
+}
+
+pub fn open_zip(path: &Path) -> Result<zip::ZipArchive<File>, String> {
+    let file = File::open(path).map_err(|e| format!("파일 열기 실패: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("ZIP 읽기 실패: {}", e))
+}
+
+/// 아카이브의 시각은 시간대 없이 기록되므로 로컬 시각으로 보고, 목록과 같은 RFC 3339로 나타낸다
+fn local_modified(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> String {
+    use chrono::TimeZone;
+
+    chrono::NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+        .and_then(|date| date.and_hms_opt(hour as u32, minute as u32, second as u32))
+        .and_then(|naive| chrono::Local.from_local_datetime(&naive).earliest())
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default()
+}
+
+fn zip_modified(date: Option<zip::DateTime>) -> String {
+    date.map(|d| {
+        local_modified(
+            d.year(),
+            d.month(),
+            d.day(),
+            d.hour(),
+            d.minute(),
+            d.second(),
+        )
+    })
+    .unwrap_or_default()
+}
+
+fn list_zip(path: &Path, limit: usize) -> Result<ArchiveListing, String> {
+    let mut archive = open_zip(path)?;
+    let mut entries = Vec::new();
+
+    for index in 0..archive.len().min(limit) {
+        // 목록만 필요하므로 압축을 풀지 않는 raw 항목으로 읽는다
+        let file = archive
+            .by_index_raw(index)
+            .map_err(|e| format!("ZIP 항목 읽기 실패: {}", e))?;
+
+        entries.push(ArchiveEntry {
+            path: file.name().trim_end_matches('/').to_string(),
+            size: file.size(),
+            modified: zip_modified(file.last_modified()),
+            is_directory: file.is_dir(),
+        });
+    }
+
+    Ok(ArchiveListing {
+        entries,
+        truncated: archive.len() > limit,
+    })
+}
+
+fn list_tar(path: &Path, format: ArchiveFormat, limit: usize) -> Result<ArchiveListing, String> {
+    let mut archive = open_tar(path, format)?;
+    let mut entries = Vec::new();
+
+    for entry in archive
+        .entries()
+        .map_err(|e| format!("TAR 읽기 실패: {}", e))?
+    {
+        if entries.len() >= limit {
+            return Ok(ArchiveListing {
+                entries,
+                truncated: true,
+            });
+        }
+
+        let entry = entry.map_err(|e| format!("TAR 항목 읽기 실패: {}", e))?;
+        let header = entry.header();
+        let modified = header
+            .mtime()
+            .ok()
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0))
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+
+        entries.push(ArchiveEntry {
+            path: entry
+                .path()
+                .map_err(|e| format!("TAR 경로 읽기 실패: {}", e))?
+                .to_string_lossy()
+                .trim_end_matches('/')
+                .to_string(),
+            size: header.size().unwrap_or(0),
+            modified,
+            is_directory: header.entry_type().is_dir(),
+        });
+    }
+
+    Ok(ArchiveListing {
+        entries,
+        truncated: false,
+    })
+}
+
+fn le_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn le_u64(data: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?))
+}
+
+/// ZIP64 확장 필드에서 원래 크기를 꺼낸다
+fn zip64_size(mut extra: &[u8]) -> Option<u64> {
+    while extra.len() >= 4 {
+        let id = le_u16(extra, 0)?;
+        let length = le_u16(extra, 2)? as usize;
+        let data = extra.get(4..4 + length)?;
+        if id == 0x0001 {
+            return le_u64(data, 0);
+        }
+        extra = &extra[4 + length..];
+    }
+    None
+}
+
+/// position의 중앙 디렉터리 레코드를 읽고 다음 레코드의 위치를 돌려준다
+fn parse_central_entry(directory: &[u8], position: usize) -> Option<(ArchiveEntry, usize)> {
+    let header = directory.get(position..position + 46)?;
+    if le_u32(header, 0)? != 0x0201_4b50 {
+        return None;
+    }
+
+    let time = le_u16(header, 12)?;
+    let date = le_u16(header, 14)?;
+    let name_end = position + 46 + le_u16(header, 28)? as usize;
+    let extra_end = name_end + le_u16(header, 30)? as usize;
+    let next = extra_end + le_u16(header, 32)? as usize;
+
+    let name = String::from_utf8_lossy(directory.get(position + 46..name_end)?).to_string();
+    let mut size = le_u32(header, 24)? as u64;
+    if size == 0xFFFF_FFFF {
+        size = zip64_size(directory.get(name_end..extra_end)?).unwrap_or(size);
+    }
+
+    let modified = local_modified(
+        1980 + (date >> 9),
+        ((date >> 5) & 0x0F) as u8,
+        (date & 0x1F) as u8,
+        (time >> 11) as u8,
+        ((time >> 5) & 0x3F) as u8,
+        ((time & 0x1F) * 2) as u8,
+    );
+
+    Some((
+        ArchiveEntry {
+            path: name.trim_end_matches('/').to_string(),
+            size,
+            modified,
+            is_directory: name.ends_with('/'),
+        },
+        next,
+    ))
+}
+
+/// 원격 ZIP의 끝부분과 중앙 디렉터리만 읽어 항목을 나열한다. 파일 전체를 내려받지 않는다.
+pub fn list_zip_ranges(
+    size: u64,
+    limit: usize,
+    read_range: &mut dyn FnMut(u64, u64) -> Result<Vec<u8>, String>,
+) -> Result<ArchiveListing, String> {
+    let invalid = || "ZIP 읽기 실패: 중앙 디렉터리를 찾을 수 없습니다".to_string();
+
+    let tail_start = size.saturating_sub(ZIP_TAIL_BYTES);
+    let tail = read_range(tail_start, size - tail_start)?;
+    let eocd = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&at| le_u32(&tail, at) == Some(0x0605_4b50))
+        .ok_or_else(invalid)?;
+
+    let mut total = le_u16(&tail, eocd + 10).ok_or_else(invalid)? as u64;
+    let mut directory_size = le_u32(&tail, eocd + 12).ok_or_else(invalid)? as u64;
+    // 중앙 디렉터리는 EOCD 바로 앞에 있다. 앞에 다른 데이터가 붙은 파일도 있어 기록된 오프셋은 쓰지 않는다.
+    let mut directory_end = tail_start + eocd as u64;
+
+    // ZIP64면 EOCD 앞의 로케이터가 ZIP64 EOCD 레코드를 가리킨다
+    if eocd >= 20 && le_u32(&tail, eocd - 20) == Some(0x0706_4b50) {
+        let offset = le_u64(&tail, eocd - 20 + 8).ok_or_else(invalid)?;
+        let record = read_range(offset, 56)?;
+        if le_u32(&record, 0) != Some(0x0606_4b50) {
+            return Err(invalid());
+        }
+        total = le_u64(&record, 32).ok_or_else(invalid)?;
+        directory_size = le_u64(&record, 40).ok_or_else(invalid)?;
+        directory_end = offset;
+    }
+
+    let directory_start = directory_end
+        .checked_sub(directory_size)
+        .ok_or_else(invalid)?;
+    let directory = read_range(
+        directory_start,
+        directory_size.min(ZIP_CENTRAL_DIRECTORY_LIMIT),
+    )?;
+
+    let mut entries = Vec::new();
+    let mut position = 0;
+    while entries.len() < limit {
+        let Some((entry, next)) = parse_central_entry(&directory, position) else {
+            break;
+        };
+        entries.push(entry);
+        position = next;
+    }
+
+    if entries.is_empty() && total > 0 {
+        return Err(invalid());
+    }
+
+    Ok(ArchiveListing {
+        truncated: (entries.len() as u64) < total,
+        entries,
+    })
+}
+
+/// 내려받은 아카이브의 항목을 압축을 풀지 않고 나열한다
+pub fn list_entries(
+    path: &Path,
+    format: ArchiveFormat,
+    limit: usize,
+) -> Result<ArchiveListing, String> {
+    match format {
+        ArchiveFormat::Zip => list_zip(path, limit),
+        _ => list_tar(path, format, limit),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractTarget {
+    Local,
+    Remote,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractSummary {
+    pub extracted: usize,
+    // 이미 있는 파일이라 그대로 둔 항목
+    pub skipped: usize,
+    // 경로가 대상 폴더를 벗어나거나(폴더 안의 링크를 거치는 경우 포함) 링크·장치 파일이라 풀지 않은 항목
+    pub rejected: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressSummary {
+    // 권한 등으로 읽지 못해 아카이브에 넣지 못한 원격 폴더
+    pub unreadable: Vec<String>,
+    // 목록을 읽은 뒤 커져서 목록의 크기만큼만 담은 파일
+    pub changed: Vec<String>,
+}
+
+/// 아카이브 안의 경로를 대상 폴더 기준의 상대 경로로 바꾼다.
+/// 절대 경로나 ".."이 들어 있어 폴더 밖을 가리키는 항목은 None이다.
+pub fn safe_relative(name: &str) -> Option<PathBuf> {
+    let name = name.replace('\\', "/");
+    let mut relative = PathBuf::new();
+
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+fn check_conflicts(path: &Path, format: ArchiveFormat, dest: &Path) -> Result<(), String> {
+    let listing = list_entries(path, format, usize::MAX)?;
+
+    for entry in listing.entries.iter().filter(|e| !e.is_directory) {
+        if let Some(relative) = safe_relative(&entry.path) {
+            if dest.join(&relative).exists() {
+                return Err(format!("이미 존재하는 파일: {}", relative.display()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 같은 이름이 있을 때 쓸 "이름 (n).확장자". 점으로 시작하는 이름은 확장자가 없는 것으로 본다.
+pub fn numbered_name(name: &str, n: usize) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!("{} ({}).{}", stem, n, extension),
+        _ => format!("{} ({})", name, n),
+    }
+}
+
+/// 대상 폴더 안에 이미 있는 링크를 따라가면 폴더 밖에 쓰게 되므로, 경로 중간이나 끝이 링크인지 본다
+fn passes_through_link(dest: &Path, relative: &Path) -> bool {
+    let mut path = dest.to_path_buf();
+
+    relative.components().any(|component| {
+        path.push(component);
+        fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink())
+    })
+}
+
+/// dest는 canonicalize된 경로여야 한다. budget은 앞으로 더 풀 수 있는 바이트 수다.
+fn write_entry(
+    dest: &Path,
+    relative: &Path,
+    is_directory: bool,
+    reader: &mut dyn Read,
+    policy: OverwritePolicy,
+    budget: &mut u64,
+    summary: &mut ExtractSummary,
+) -> Result<(), String> {
+    let mut target = dest.join(relative);
+
+    if passes_through_link(dest, relative) {
+        summary.rejected += 1;
+        return Ok(());
+    }
+
+    if is_directory {
+        return fs::create_dir_all(&target).map_err(|e| format!("폴더 생성 실패: {}", e));
+    }
+
+    if target.exists() {
+        match policy {
+            OverwritePolicy::Overwrite => {}
+            OverwritePolicy::Skip => {
+                summary.skipped += 1;
+                return Ok(());
+            }
+            OverwritePolicy::Ask => {
+                return Err(format!("이미 존재하는 파일: {}", relative.display()));
+            }
+            OverwritePolicy::Rename => {
+                let name = target
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                target = (1..)
+                    .map(|n| target.with_file_name(numbered_name(&name, n)))
+                    .find(|candidate| !candidate.exists())
+                    .unwrap_or(target);
+            }
+        }
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("폴더 생성 실패: {}", e))?;
+
+        // 확인과 생성 사이에 링크로 바뀐 경우까지 막기 위해 실제 위치를 다시 확인한다
+        let inside = fs::canonicalize(parent).is_ok_and(|parent| parent.starts_with(dest));
+        if !inside {
+            summary.rejected += 1;
+            return Ok(());
+        }
+    }
+
+    let mut file = File::create(&target).map_err(|e| format!("파일 생성 실패: {}", e))?;
+    let written = std::io::copy(&mut reader.take(*budget + 1), &mut file)
+        .map_err(|e| format!("압축 해제 실패: {}", e))?;
+
+    if written > *budget {
+        drop(file);
+        fs::remove_file(&target).ok();
+        return Err(format!(
+            "압축을 푼 크기가 너무 큽니다 ({}GB 초과)",
+            EXTRACT_SIZE_LIMIT / 1024 / 1024 / 1024
+        ));
+    }
+    *budget -= written;
+
+    summary.extracted += 1;
+    Ok(())
+}
+
+fn extract_zip(
+    path: &Path,
+    dest: &Path,
+    policy: OverwritePolicy,
+) -> Result<ExtractSummary, String> {
+    let mut archive = open_zip(path)?;
+    let mut summary = ExtractSummary::default();
+    let mut budget = EXTRACT_SIZE_LIMIT;
+
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .map_err(|e| format!("ZIP 항목 읽기 실패: {}", e))?;
+
+        let is_symlink = file.unix_mode().is_some_and(|m| m & 0o170000 == 0o120000);
+        let Some(relative) = safe_relative(file.name()).filter(|_| !is_symlink) else {
+            summary.rejected += 1;
+            continue;
+        };
+
+        let is_directory = file.is_dir();
+        write_entry(
+            dest,
+            &relative,
+            is_directory,
+            &mut file,
+            policy,
+            &mut budget,
+            &mut summary,
+        )?;
+    }
+
+    Ok(summary)
+}
+
+fn extract_tar(
+    path: &Path,
+    format: ArchiveFormat,
+    dest: &Path,
+    policy: OverwritePolicy,
+) -> Result<ExtractSummary, String> {
+    let mut archive = open_tar(path, format)?;
+    let mut summary = ExtractSummary::default();
+    let mut budget = EXTRACT_SIZE_LIMIT;
+
+    for entry in archive
+        .entries()
+        .map_err(|e| format!("TAR 읽기 실패: {}", e))?
+    {
+        let mut entry = entry.map_err(|e| format!("TAR 항목 읽기 실패: {}", e))?;
+        let entry_type = entry.header().entry_type();
+
+        // 링크는 대상 폴더 밖을 가리킬 수 있으므로 일반 파일과 폴더만 푼다
+        let relative = entry
+            .path()
+            .ok()
+            .and_then(|p| safe_relative(&p.to_string_lossy()));
+        let Some(relative) = relative.filter(|_| entry_type.is_file() || entry_type.is_dir())
+        else {
+            summary.rejected += 1;
+            continue;
+        };
+
+        write_entry(
+            dest,
+            &relative,
+            entry_type.is_dir(),
+            &mut entry,
+            policy,
+            &mut budget,
+            &mut summary,
+        )?;
+    }
+
+    Ok(summary)
+}
+
+/// 내려받은 아카이브를 dest 폴더에 푼다. Ask 정책이면 아무것도 쓰기 전에 충돌부터 확인한다.
+pub fn extract(
+    path: &Path,
+    format: ArchiveFormat,
+    dest: &Path,
+    policy: OverwritePolicy,
+) -> Result<ExtractSummary, String> {
+    fs::create_dir_all(dest).map_err(|e| format!("폴더 생성 실패: {}", e))?;
+    let dest = fs::canonicalize(dest).map_err(|e| format!("경로 처리 실패: {}", e))?;
+
+    if policy == OverwritePolicy::Ask {
+        check_conflicts(path, format, &dest)?;
+    }
+
+    match format {
+        ArchiveFormat::Zip => extract_zip(path, &dest, policy),
+        _ => extract_tar(path, format, &dest, policy),
+    }
+}
+
+/// 목록에서 얻은 크기만큼만 읽는다. 그보다 먼저 끝나면 tar 항목이 깨지므로 오류로 본다.
+struct SizedReader<'a> {
+    inner: &'a mut dyn Read,
+    remaining: u64,
+}
+
+impl Read for SizedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 {
+            return Ok(0);
+        }
+
+        let max = buf.len().min(self.remaining as usize);
+        let read = self.inner.read(&mut buf[..max])?;
+        if read == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "파일 크기가 목록과 다릅니다",
+            ));
+        }
+
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+fn zip_time(modified: Option<chrono::NaiveDateTime>) -> zip::DateTime {
+    use chrono::{Datelike, Timelike};
+
+    modified
+        .and_then(|t| {
+            zip::DateTime::from_date_and_time(
+                t.year() as u16,
+                t.month() as u8,
+                t.day() as u8,
+                t.hour() as u8,
+                t.minute() as u8,
+                t.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+fn tar_header(
+    kind: tar::EntryType,
+    size: u64,
+    modified: Option<chrono::NaiveDateTime>,
+) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(kind);
+    header.set_size(size);
+    header.set_mode(if kind.is_dir() { 0o755 } else { 0o644 });
+    header.set_mtime(
+        modified
+            .map(|t| t.and_utc().timestamp().max(0) as u64)
+            .unwrap_or(0),
+    );
+    header
+}
+
+/// 크기를 모르는 파일을 잠시 받아 둘 임시 파일. 남은 파일은 temp_files::cleanup_stale이 치운다.
+fn spool_path() -> PathBuf {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    std::env::temp_dir().join(format!(
+        "easyftp_compress_{}_{:x}_{}.spool",
+        std::process::id(),
+        nanos,
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// 압축 스트림의 마지막 블록까지 쓰고 그 결과를 확인하기 위한 출력
+pub trait ArchiveSink: Write {
+    fn finish_sink(self: Box<Self>) -> std::io::Result<()>;
+}
+
+impl ArchiveSink for File {
+    fn finish_sink(self: Box<Self>) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl ArchiveSink for flate2::write::GzEncoder<File> {
+    fn finish_sink(self: Box<Self>) -> std::io::Result<()> {
+        self.finish().map(|_| ())
+    }
+}
+
+impl ArchiveSink for bzip2::write::BzEncoder<File> {
+    fn finish_sink(self: Box<Self>) -> std::io::Result<()> {
+        self.finish().map(|_| ())
+    }
+}
+
+/// 원격 파일을 임시 폴더를 거치지 않고 바로 아카이브에 써 넣는다
+pub enum ArchiveWriter {
+    Zip(Box<zip::ZipWriter<File>>),
+    Tar(tar::Builder<Box<dyn ArchiveSink>>),
+}
+
+impl ArchiveWriter {
+    pub fn create(dest: &Path, format: ArchiveFormat) -> Result<Self, String> {
+        let file = File::create(dest).map_err(|e| format!("파일 생성 실패: {}", e))?;
+
+        let writer: Box<dyn ArchiveSink> = match format {
+            ArchiveFormat::Zip => return Ok(Self::Zip(Box::new(zip::ZipWriter::new(file)))),
+            ArchiveFormat::Tar => Box::new(file),
+            ArchiveFormat::TarGz => Box::new(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )),
+            ArchiveFormat::TarBz2 => Box::new(bzip2::write::BzEncoder::new(
+                file,
+                bzip2::Compression::default(),
+            )),
+        };
+
+        Ok(Self::Tar(tar::Builder::new(writer)))
+    }
+
+    pub fn add_directory(
+        &mut self,
+        name: &str,
+        modified: Option<chrono::NaiveDateTime>,
+    ) -> Result<(), String> {
+        match self {
+            Self::Zip(zip) => {
+                let options =
+                    zip::write::SimpleFileOptions::default().last_modified_time(zip_time(modified));
+                zip.add_directory(name, options)
+                    .map_err(|e| format!("ZIP 디렉토리 추가 실패: {}", e))
+            }
+            Self::Tar(tar) => {
+                let mut header = tar_header(tar::EntryType::Directory, 0, modified);
+                tar.append_data(&mut header, name, std::io::empty())
+                    .map_err(|e| format!("TAR 디렉토리 추가 실패: {}", e))
+            }
+        }
+    }
+
+    /// 파일 하나를 아카이브에 넣는다. 목록을 읽은 뒤 파일이 커져 size만큼만 담았으면 true를 돌려준다.
+    /// size를 모르면(FTP에서 SIZE를 지원하지 않는 경우) tar 헤더를 쓰기 위해 임시 파일에 먼저 받는다.
+    pub fn add_file(
+        &mut self,
+        name: &str,
+        size: Option<u64>,
+        modified: Option<chrono::NaiveDateTime>,
+        reader: &mut dyn Read,
+    ) -> std::io::Result<bool> {
+        match self {
+            Self::Zip(zip) => {
+                // 크기를 모르면 4GB를 넘을 수도 있으므로 ZIP64 헤더를 쓴다
+                let large = size.is_none_or(|size| size >= u32::MAX as u64);
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .last_modified_time(zip_time(modified))
+                    .large_file(large);
+                zip.start_file(name, options)
+                    .map_err(std::io::Error::other)?;
+                std::io::copy(reader, zip)?;
+                Ok(false)
+            }
+            Self::Tar(tar) => match size {
+                Some(size) => {
+                    let mut header = tar_header(tar::EntryType::Regular, size, modified);
+                    let sized = SizedReader {
+                        inner: &mut *reader,
+                        remaining: size,
+                    };
+                    tar.append_data(&mut header, name, sized)?;
+                    // 헤더에 쓴 크기를 바꿀 수 없으므로 늘어난 부분은 읽어서 버리고 전송을 정상적으로 끝낸다
+                    let grown = std::io::copy(reader, &mut std::io::sink())?;
+                    Ok(grown > 0)
+                }
+                None => {
+                    let path = spool_path();
+                    let result = File::options()
+                        .read(true)
+                        .write(true)
+                        .create_new(true)
+                        .open(&path)
+                        .and_then(|mut spool| {
+                            let size = std::io::copy(reader, &mut spool)?;
+                            spool.seek(SeekFrom::Start(0))?;
+                            let mut header = tar_header(tar::EntryType::Regular, size, modified);
+                            tar.append_data(&mut header, name, spool)
+                        });
+                    fs::remove_file(&path).ok();
+                    result.map(|_| false)
+                }
+            },
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self {
+            Self::Zip(zip) => zip
+                .finish()
+                .map(|_| ())
+                .map_err(|e| format!("ZIP 완료 실패: {}", e)),
+            Self::Tar(tar) => tar
+                .into_inner()
+                .and_then(|sink| sink.finish_sink())
+                .map_err(|e| format!("TAR 완료 실패: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample_zip(prefix: &[u8]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(prefix.to_vec()));
+        let options = zip::write::SimpleFileOptions::default()
+            .last_modified_time(zip::DateTime::from_date_and_time(2024, 3, 5, 14, 30, 10).unwrap());
+
+        writer.add_directory("docs/", options).unwrap();
+        writer.start_file("docs/한글.txt", options).unwrap();
+        writer.write_all(b"hello world").unwrap();
+        writer.start_file("readme.md", options).unwrap();
+        writer.write_all(b"# readme").unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn list_bytes(data: &[u8], limit: usize) -> Result<ArchiveListing, String> {
+        list_zip_ranges(data.len() as u64, limit, &mut |offset, length| {
+            let start = offset as usize;
+            let end = (start + length as usize).min(data.len());
+            Ok(data[start..end].to_vec())
+        })
+    }
+
+    #[test]
+    fn list_zip_ranges_reads_central_directory() {
+        let listing = list_bytes(&sample_zip(b""), 100).unwrap();
+
+        assert!(!listing.truncated);
+        let paths: Vec<&str> = listing.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["docs", "docs/한글.txt", "readme.md"]);
+        assert!(listing.entries[0].is_directory);
+        assert_eq!(listing.entries[1].size, 11);
+
+        let modified = chrono::DateTime::parse_from_rfc3339(&listing.entries[1].modified).unwrap();
+        assert_eq!(
+            modified.naive_local(),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 5)
+                .unwrap()
+                .and_hms_opt(14, 30, 10)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn list_zip_ranges_handles_prefix_and_limit() {
+        let listing = list_bytes(&sample_zip(b"self-extractor stub"), 2).unwrap();
+
+        assert!(listing.truncated);
+        assert_eq!(listing.entries.len(), 2);
+        assert_eq!(listing.entries[1].path, "docs/한글.txt");
+    }
+
+    #[test]
+    fn list_zip_ranges_rejects_non_zip() {
+        assert!(list_bytes(b"not a zip archive", 10).is_err());
+        assert!(list_bytes(b"", 10).is_err());
+    }
+
+    #[test]
+    fn server_compress_command_runs_from_parent() {
+        assert_eq!(
+            server_compress_command(
+                "/home/user/my dir/",
+                ArchiveFormat::TarGz,
+                "/tmp/x/archive.tar.gz"
+            )
+            .unwrap(),
+            "cd '/home/user' && tar -czf '/tmp/x/archive.tar.gz' -- 'my dir'"
+        );
+        assert_eq!(
+            server_compress_command("/data.txt", ArchiveFormat::Zip, "/tmp/x/archive.zip").unwrap(),
+            "cd '/' && zip -qr '/tmp/x/archive.zip' 'data.txt'"
+        );
+        assert_eq!(
+            server_compress_command("/srv/it's", ArchiveFormat::TarBz2, "/tmp/x/a").unwrap(),
+            "cd '/srv' && tar -cjf '/tmp/x/a' -- 'it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn server_compress_command_rejects_unsupported_paths() {
+        assert_eq!(
+            server_compress_command("/", ArchiveFormat::Tar, "/tmp/x/a"),
+            None
+        );
+        assert_eq!(
+            server_compress_command("relative", ArchiveFormat::Tar, "/tmp/x/a"),
+            None
+        );
+        assert_eq!(
+            server_compress_command("/srv/-rf", ArchiveFormat::Zip, "/tmp/x/a"),
+            None
+        );
+        assert!(server_compress_command("/srv/-rf", ArchiveFormat::Tar, "/tmp/x/a").is_some());
+    }
+
+    #[test]
+    fn parse_server_temp_dir_accepts_only_mktemp_output() {
+        assert_eq!(
+            parse_server_temp_dir(b"/tmp/easyftp_compress_a1B2c3D4e5\n").as_deref(),
+            Some("/tmp/easyftp_compress_a1B2c3D4e5")
+        );
+        assert_eq!(parse_server_temp_dir(b"/tmp/easyftp_compress_\n"), None);
+        assert_eq!(
+            parse_server_temp_dir(b"/tmp/easyftp_compress_x/../etc"),
+            None
+        );
+        assert_eq!(
+            parse_server_temp_dir(b"/home/user/easyftp_compress_abc"),
+            None
+        );
+        assert_eq!(parse_server_temp_dir(b""), None);
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("easyftp-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::canonicalize(dir).unwrap()
+    }
+
+    #[test]
+    fn safe_relative_keeps_paths_inside() {
+        assert_eq!(safe_relative("a/b.txt"), Some(PathBuf::from("a/b.txt")));
+        assert_eq!(safe_relative("./a/./b"), Some(PathBuf::from("a/b")));
+        assert_eq!(safe_relative("a\\b.txt"), Some(PathBuf::from("a/b.txt")));
+        assert_eq!(safe_relative("dir/"), Some(PathBuf::from("dir")));
+    }
+
+    #[test]
+    fn safe_relative_rejects_escaping_paths() {
+        assert_eq!(safe_relative("../etc/passwd"), None);
+        assert_eq!(safe_relative("a/../../b"), None);
+        assert_eq!(safe_relative("a/.."), None);
+        assert_eq!(safe_relative("/etc/passwd"), None);
+        assert_eq!(safe_relative("..\\windows\\system32"), None);
+        assert_eq!(safe_relative(""), None);
+        assert_eq!(safe_relative("."), None);
+    }
+
+    #[test]
+    fn write_entry_stops_at_size_budget() {
+        let dest = scratch_dir("budget");
+        let mut summary = ExtractSummary::default();
+        let mut budget = 10;
+
+        write_entry(
+            &dest,
+            Path::new("small.txt"),
+            false,
+            &mut &b"12345"[..],
+            OverwritePolicy::Ask,
+            &mut budget,
+            &mut summary,
+        )
+        .unwrap();
+        assert_eq!(budget, 5);
+
+        let result = write_entry(
+            &dest,
+            Path::new("large.txt"),
+            false,
+            &mut &b"123456"[..],
+            OverwritePolicy::Ask,
+            &mut budget,
+            &mut summary,
+        );
+        assert!(result.is_err());
+        assert!(!dest.join("large.txt").exists());
+        assert_eq!(summary.extracted, 1);
+
+        fs::remove_dir_all(&dest).ok();
+    }
+
+    #[test]
+    fn numbered_name_keeps_extension() {
+        assert_eq!(numbered_name("report.txt", 1), "report (1).txt");
+        assert_eq!(numbered_name("archive.tar.gz", 2), "archive.tar (2).gz");
+        assert_eq!(numbered_name("README", 3), "README (3)");
+        assert_eq!(numbered_name(".bashrc", 1), ".bashrc (1)");
+    }
+
+    #[test]
+    fn write_entry_applies_overwrite_policy() {
+        let dest = scratch_dir("policy");
+        fs::write(dest.join("a.txt"), "old").unwrap();
+
+        let mut summary = ExtractSummary::default();
+        let mut budget = EXTRACT_SIZE_LIMIT;
+        let mut write = |policy, summary: &mut ExtractSummary| {
+            write_entry(
+                &dest,
+                Path::new("a.txt"),
+                false,
+                &mut &b"new"[..],
+                policy,
+                &mut budget,
+                summary,
+            )
+        };
+
+        assert!(write(OverwritePolicy::Ask, &mut summary).is_err());
+        write(OverwritePolicy::Skip, &mut summary).unwrap();
+        assert_eq!(summary.skipped, 1);
+        write(OverwritePolicy::Rename, &mut summary).unwrap();
+        write(OverwritePolicy::Rename, &mut summary).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(dest.join("a (1).txt")).unwrap(), "new");
+        assert!(dest.join("a (2).txt").exists());
+        write(OverwritePolicy::Overwrite, &mut summary).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+        assert_eq!(summary.extracted, 3);
+
+        fs::remove_dir_all(&dest).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_entry_does_not_follow_links_in_dest() {
+        let dest = scratch_dir("link-dest");
+        let outside = scratch_dir("link-outside");
+        std::os::unix::fs::symlink(&outside, dest.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.join("target.txt"), dest.join("file.txt")).unwrap();
+
+        let mut summary = ExtractSummary::default();
+        let mut budget = EXTRACT_SIZE_LIMIT;
+        for relative in ["escape/evil.txt", "file.txt"] {
+            write_entry(
+                &dest,
+                Path::new(relative),
+                false,
+                &mut &b"data"[..],
+                OverwritePolicy::Overwrite,
+                &mut budget,
+                &mut summary,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(summary.rejected, 2);
+        assert_eq!(summary.extracted, 0);
+        assert!(fs::read_dir(&outside).unwrap().next().is_none());
+
+        fs::remove_dir_all(&dest).ok();
+        fs::remove_dir_all(&outside).ok();
+    }
+
+    #[test]
+    fn archive_writer_spools_unknown_sizes_and_reports_growth() {
+        let dir = scratch_dir("writer");
+        let path = dir.join("out.tar");
+
+        let mut writer = ArchiveWriter::create(&path, ArchiveFormat::Tar).unwrap();
+        let unknown = writer
+            .add_file("unknown.txt", None, None, &mut &b"spooled data"[..])
+            .unwrap();
+        let grown = writer
+            .add_file("grown.txt", Some(4), None, &mut &b"grown data"[..])
+            .unwrap();
+        let exact = writer
+            .add_file("exact.txt", Some(5), None, &mut &b"exact"[..])
+            .unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!((unknown, grown, exact), (false, true, false));
+
+        let listing = list_entries(&path, ArchiveFormat::Tar, 10).unwrap();
+        let sizes: Vec<(&str, u64)> = listing
+            .entries
+            .iter()
+            .map(|e| (e.path.as_str(), e.size))
+            .collect();
+        assert_eq!(
+            sizes,
+            [("unknown.txt", 12), ("grown.txt", 4), ("exact.txt", 5)]
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn archive_writer_rejects_shrunk_files() {
+        let dir = scratch_dir("writer-shrunk");
+        let mut writer = ArchiveWriter::create(&dir.join("out.tar"), ArchiveFormat::Tar).unwrap();
+
+        assert!(writer
+            .add_file("shrunk.txt", Some(10), None, &mut &b"short"[..])
+            .is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn zip_modified_uses_rfc3339() {
+        let zip = zip_modified(zip::DateTime::from_date_and_time(2020, 1, 2, 3, 4, 6).ok());
+        assert!(chrono::DateTime::parse_from_rfc3339(&zip).is_ok());
+        assert_eq!(zip_modified(None), "");
+    }
+}
@@ -0,0 +1,506 @@
+use crate::bookmark_import::{default_port, ImportReport};
+use crate::storage;
+use crate::Connection;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::num::NonZeroU32;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+const KDF_NAME: &str = "pbkdf2-sha256";
+const KDF_ITERATIONS: u32 = 100_000;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookmarkFile {
+    version: u32,
+    #[serde(default)]
+    exported_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<Encryption>,
+    #[serde(default)]
+    folders: Vec<String>,
+    #[serde(default)]
+    bookmarks: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Encryption {
+    kdf: String,
+    iterations: u32,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct BookmarkRecord {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    protocol: String,
+    #[serde(default)]
+    host: String,
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted_password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    share: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    identity_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proxy_jump: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey, String> {
+    let iterations = NonZeroU32::new(iterations).ok_or("잘못된 암호화 설정입니다")?;
+
+    let mut key_bytes = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key_bytes,
+    );
+
+    let unbound = UnboundKey::new(&AES_256_GCM, &key_bytes).map_err(|_| "암호화 키 생성 실패")?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+fn encrypt_password(
+    key: &LessSafeKey,
+    rng: &SystemRandom,
+    password: &str,
+) -> Result<String, String> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce_bytes)
+        .map_err(|_| "난수 생성 실패".to_string())?;
+
+    let mut in_out = password.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce_bytes),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| "비밀번호 암호화 실패".to_string())?;
+
+    let mut sealed = nonce_bytes.to_vec();
+    sealed.extend_from_slice(&in_out);
+    Ok(STANDARD.encode(sealed))
+}
+
+fn decrypt_password(key: &LessSafeKey, encoded: &str) -> Result<String, String> {
+    let sealed = STANDARD
+        .decode(encoded)
+        .map_err(|_| "암호화된 비밀번호 형식이 올바르지 않습니다".to_string())?;
+    if sealed.len() < NONCE_LEN {
+        return Err("암호화된 비밀번호 형식이 올바르지 않습니다".to_string());
+    }
+
+    let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+        .map_err(|_| "암호화된 비밀번호 형식이 올바르지 않습니다".to_string())?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plain = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| "암호가 올바르지 않습니다".to_string())?;
+
+    String::from_utf8(plain.to_vec()).map_err(|_| "비밀번호 복호화 실패".to_string())
+}
+
+fn validate(record: BookmarkRecord, password: String) -> Result<Connection, String> {
+    let protocol = record.protocol.trim().to_lowercase();
+    if !matches!(protocol.as_str(), "ftp" | "sftp" | "smb") {
+        return Err(format!("지원하지 않는 프로토콜 ({})", record.protocol));
+    }
+
+    let host = record.host.trim().to_string();
+    if host.is_empty() {
+        return Err("호스트가 없습니다".to_string());
+    }
+    if host.contains(char::is_whitespace) {
+        return Err("호스트에 공백이 포함되어 있습니다".to_string());
+    }
+
+    let port = match record.port {
+        Some(0) => return Err("포트 번호가 올바르지 않습니다".to_string()),
+        Some(port) => port,
+        None => default_port(&protocol),
+    };
+
+    let share = record.share.filter(|s| !s.trim().is_empty());
+    if protocol == "smb" && share.is_none() {
+        return Err("SMB 연결에는 공유폴더 이름이 필요합니다".to_string());
+    }
+
+    let tags = storage::normalize_tags(record.tags);
+
+    let name = Some(record.name.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| host.clone());
+
+    Ok(Connection {
+        id: crate::uuid_simple(),
+        name,
+        protocol,
+        host,
+        port,
+        username: record.username.trim().to_string(),
+        password,
+        share,
+        identity_file: record.identity_file.filter(|s| !s.is_empty()),
+        proxy_jump: record.proxy_jump.filter(|s| !s.is_empty()),
+        folder: record.folder.as_deref().and_then(storage::normalize_folder),
+        tags,
+        ..Default::default()
+    })
+}
+
+pub fn export_to(
+    file_path: &str,
+    connections: &[Connection],
+    passphrase: Option<&str>,
+) -> Result<(), String> {
+    let content = export_content(connections, passphrase)?;
+    fs::write(file_path, content).map_err(|e| format!("파일 저장 실패: {}", e))
+}
+
+fn export_content(connections: &[Connection], passphrase: Option<&str>) -> Result<String, String> {
+    let rng = SystemRandom::new();
+
+    let (encryption, key) = match passphrase {
+        Some(passphrase) => {
+            let mut salt = [0u8; 16];
+            rng.fill(&mut salt)
+                .map_err(|_| "난수 생성 실패".to_string())?;
+            let key = derive_key(passphrase, &salt, KDF_ITERATIONS)?;
+            let encryption = Encryption {
+                kdf: KDF_NAME.to_string(),
+                iterations: KDF_ITERATIONS,
+                salt: STANDARD.encode(salt),
+            };
+            (Some(encryption), Some(key))
+        }
+        None => (None, None),
+    };
+
+    let mut folders: Vec<String> = Vec::new();
+    let mut bookmarks = Vec::new();
+
+    for conn in connections {
+        if let Some(folder) = &conn.folder {
+            if !folders.contains(folder) {
+                folders.push(folder.clone());
+            }
+        }
+
+        // 암호 없이 내보낼 때는 비밀번호를 포함하지 않는다
+        let encrypted_password = match &key {
+            Some(key) if !conn.password.is_empty() => {
+                Some(encrypt_password(key, &rng, &conn.password)?)
+            }
+            _ => None,
+        };
+
+        let record = BookmarkRecord {
+            name: conn.name.clone(),
+            protocol: conn.protocol.clone(),
+            host: conn.host.clone(),
+            port: Some(conn.port),
+            username: conn.username.clone(),
+            password: None,
+            encrypted_password,
+            share: conn.share.clone(),
+            identity_file: conn.identity_file.clone(),
+            proxy_jump: conn.proxy_jump.clone(),
+            folder: conn.folder.clone(),
+            tags: conn.tags.clone(),
+        };
+        bookmarks
+            .push(serde_json::to_value(record).map_err(|e| format!("JSON 직렬화 실패: {}", e))?);
+    }
+
+    folders.sort();
+
+    let file = BookmarkFile {
+        version: SCHEMA_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        encryption,
+        folders,
+        bookmarks,
+    };
+
+    serde_json::to_string_pretty(&file).map_err(|e| format!("JSON 직렬화 실패: {}", e))
+}
+
+pub fn import_from(file_path: &str, passphrase: Option<&str>) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(file_path).map_err(|e| format!("파일 읽기 실패: {}", e))?;
+    import_content(&content, passphrase)
+}
+
+/// 암호화된 비밀번호가 하나도 풀리지 않으면 암호가 틀린 것으로 본다.
+/// 일부만 풀리지 않으면 그 항목만 건너뛴다.
+fn check_passphrase(key: &LessSafeKey, bookmarks: &[serde_json::Value]) -> Result<(), String> {
+    let mut encrypted = bookmarks
+        .iter()
+        .filter_map(|b| b.get("encryptedPassword").and_then(|p| p.as_str()))
+        .peekable();
+
+    if encrypted.peek().is_none() || encrypted.any(|e| decrypt_password(key, e).is_ok()) {
+        Ok(())
+    } else {
+        Err("암호가 올바르지 않습니다".to_string())
+    }
+}
+
+fn import_content(content: &str, passphrase: Option<&str>) -> Result<ImportReport, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("JSON 파싱 실패: {}", e))?;
+
+    // 버전 필드가 없는 배열은 이전 버전의 내보내기 형식
+    let file = match value {
+        serde_json::Value::Array(bookmarks) => BookmarkFile {
+            version: 0,
+            exported_at: String::new(),
+            encryption: None,
+            folders: Vec::new(),
+            bookmarks,
+        },
+        value => serde_json::from_value::<BookmarkFile>(value)
+            .map_err(|e| format!("북마크 파일 형식이 올바르지 않습니다: {}", e))?,
+    };
+
+    if file.version > SCHEMA_VERSION {
+        return Err(format!(
+            "지원하지 않는 북마크 파일 버전입니다 (v{})",
+            file.version
+        ));
+    }
+
+    let key = match (&file.encryption, passphrase) {
+        (Some(encryption), Some(passphrase)) => {
+            if encryption.kdf != KDF_NAME {
+                return Err(format!("지원하지 않는 암호화 방식: {}", encryption.kdf));
+            }
+            let salt = STANDARD
+                .decode(&encryption.salt)
+                .map_err(|_| "암호화 설정이 올바르지 않습니다".to_string())?;
+            Some(derive_key(passphrase, &salt, encryption.iterations)?)
+        }
+        (Some(_), None) => {
+            return Err("암호화된 북마크 파일입니다. 암호를 입력해주세요.".to_string())
+        }
+        (None, _) => None,
+    };
+
+    if let Some(key) = &key {
+        check_passphrase(key, &file.bookmarks)?;
+    }
+
+    let mut report = ImportReport::default();
+
+    for (index, value) in file.bookmarks.into_iter().enumerate() {
+        let record: BookmarkRecord = match serde_json::from_value(value) {
+            Ok(record) => record,
+            Err(e) => {
+                report.skip(&format!("#{}", index + 1), format!("형식 오류: {}", e));
+                continue;
+            }
+        };
+
+        let label = Some(record.name.clone())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("#{}", index + 1));
+
+        let password = match (&record.encrypted_password, &key) {
+            (Some(encrypted), Some(key)) => match decrypt_password(key, encrypted) {
+                Ok(password) => password,
+                Err(reason) => {
+                    report.skip(&label, reason);
+                    continue;
+                }
+            },
+            (Some(_), None) => {
+                report.skip(&label, "암호화 정보 없이 암호화된 비밀번호가 있습니다");
+                continue;
+            }
+            (None, _) => record.password.clone().unwrap_or_default(),
+        };
+
+        let conn = match validate(record, password) {
+            Ok(conn) => conn,
+            Err(reason) => {
+                report.skip(&label, reason);
+                continue;
+            }
+        };
+
+        if report
+            .imported
+            .iter()
+            .any(|c| storage::is_same_endpoint(c, &conn))
+        {
+            report.skip(&label, "파일 안에 중복된 북마크");
+            continue;
+        }
+
+        report.imported.push(conn);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(name: &str, host: &str, password: &str) -> Connection {
+        Connection {
+            id: name.to_string(),
+            name: name.to_string(),
+            protocol: "sftp".to_string(),
+            host: host.to_string(),
+            port: 22,
+            username: "deploy".to_string(),
+            password: password.to_string(),
+            folder: Some("회사/운영".to_string()),
+            tags: vec!["prod".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn skipped_names(report: &ImportReport) -> Vec<&str> {
+        report.skipped.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn round_trip_without_passphrase_drops_passwords() {
+        let exported =
+            export_content(&[connection("web", "web.example.com", "secret")], None).unwrap();
+        assert!(!exported.contains("secret"));
+
+        let report = import_content(&exported, None).unwrap();
+        assert!(report.skipped.is_empty());
+        let imported = &report.imported[0];
+        assert_eq!(imported.name, "web");
+        assert_eq!(imported.host, "web.example.com");
+        assert_eq!(imported.port, 22);
+        assert_eq!(imported.password, "");
+        assert_eq!(imported.folder.as_deref(), Some("회사/운영"));
+        assert_eq!(imported.tags, ["prod"]);
+    }
+
+    #[test]
+    fn round_trip_with_passphrase_restores_passwords() {
+        let connections = [
+            connection("web", "web.example.com", "secret"),
+            connection("db", "db.example.com", ""),
+        ];
+        let exported = export_content(&connections, Some("correct horse")).unwrap();
+        assert!(!exported.contains("secret"));
+
+        let report = import_content(&exported, Some("correct horse")).unwrap();
+        let passwords: Vec<&str> = report
+            .imported
+            .iter()
+            .map(|c| c.password.as_str())
+            .collect();
+        assert_eq!(passwords, ["secret", ""]);
+    }
+
+    #[test]
+    fn encrypted_file_needs_the_right_passphrase() {
+        let exported = export_content(
+            &[connection("web", "web.example.com", "secret")],
+            Some("right"),
+        )
+        .unwrap();
+
+        let missing = import_content(&exported, None).unwrap_err();
+        assert!(missing.starts_with("암호화된 북마크 파일입니다"));
+        assert_eq!(
+            import_content(&exported, Some("wrong")).unwrap_err(),
+            "암호가 올바르지 않습니다"
+        );
+    }
+
+    #[test]
+    fn corrupt_encrypted_password_skips_only_that_bookmark() {
+        let connections = [
+            connection("web", "web.example.com", "secret"),
+            connection("db", "db.example.com", "other"),
+        ];
+        let exported = export_content(&connections, Some("pass")).unwrap();
+
+        let mut file: serde_json::Value = serde_json::from_str(&exported).unwrap();
+        file["bookmarks"][1]["encryptedPassword"] = "AAAAAAAAAAAAAAAAAAAAAAAA".into();
+        let report = import_content(&file.to_string(), Some("pass")).unwrap();
+
+        assert_eq!(report.imported.len(), 1);
+        assert_eq!(report.imported[0].password, "secret");
+        assert_eq!(skipped_names(&report), ["db"]);
+    }
+
+    #[test]
+    fn imports_legacy_array_format() {
+        let path = format!(
+            "{}/tests/fixtures/bookmarks/easyftp-legacy.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let report = import_from(&path, None).unwrap();
+
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.imported[0].protocol, "ftp");
+        assert_eq!(report.imported[0].port, 21);
+        assert_eq!(report.imported[0].password, "plain-secret");
+        assert_eq!(report.imported[1].share.as_deref(), Some("public"));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let error = import_content(r#"{"version": 99, "bookmarks": []}"#, None).unwrap_err();
+        assert!(error.contains("v99"));
+    }
+
+    #[test]
+    fn validates_and_dedups_bookmarks() {
+        let content = r#"{
+            "version": 1,
+            "bookmarks": [
+                {"name": "ok", "protocol": "sftp", "host": "a.example.com", "tags": ["Prod", "prod", " "]},
+                {"name": "dup", "protocol": "SFTP", "host": "a.example.com"},
+                {"name": "proto", "protocol": "http", "host": "b.example.com"},
+                {"name": "nohost", "protocol": "ftp", "host": " "},
+                {"name": "space", "protocol": "ftp", "host": "bad host"},
+                {"name": "port", "protocol": "ftp", "host": "c.example.com", "port": 0},
+                {"name": "share", "protocol": "smb", "host": "nas"},
+                {"protocol": "ftp", "host": 5},
+                {"protocol": "ftp", "host": "d.example.com"}
+            ]
+        }"#;
+        let report = import_content(content, None).unwrap();
+
+        let names: Vec<&str> = report.imported.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["ok", "d.example.com"]);
+        assert_eq!(report.imported[0].tags, ["Prod"]);
+        assert_eq!(
+            skipped_names(&report),
+            ["dup", "proto", "nohost", "space", "port", "share", "#8"]
+        );
+    }
+}
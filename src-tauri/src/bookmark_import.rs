@@ -0,0 +1,475 @@
+use crate::Connection;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntry {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub imported: Vec<Connection>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+impl ImportReport {
+    pub fn skip(&mut self, name: &str, reason: impl Into<String>) {
+        self.skipped.push(SkippedEntry {
+            name: name.to_string(),
+            reason: reason.into(),
+        });
+    }
+}
+
+pub fn import_from(source: &str, file_path: &str) -> Result<ImportReport, String> {
+    match source {
+        "filezilla" => import_filezilla(file_path),
+        "cyberduck" => import_cyberduck(file_path),
+        "winscp" => import_winscp(file_path),
+        _ => Err(format!("지원하지 않는 가져오기 형식: {}", source)),
+    }
+}
+
+fn new_connection(
+    name: &str,
+    protocol: &str,
+    host: &str,
+    port: u16,
+    username: &str,
+    password: &str,
+) -> Connection {
+    Connection {
+        id: crate::uuid_simple(),
+        name: name.to_string(),
+        protocol: protocol.to_string(),
+        host: host.to_string(),
+        port,
+        username: username.to_string(),
+        password: password.to_string(),
+        share: None,
+        ..Default::default()
+    }
+}
+
+pub fn default_port(protocol: &str) -> u16 {
+    match protocol {
+        "ftp" => 21,
+        "smb" => 445,
+        _ => 22,
+    }
+}
+
+pub fn import_filezilla(file_path: &str) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(file_path).map_err(|e| format!("파일 읽기 실패: {}", e))?;
+    let doc =
+        roxmltree::Document::parse(&content).map_err(|e| format!("XML 파싱 실패: {}", e))?;
+
+    let mut report = ImportReport::default();
+
+    for server in doc.descendants().filter(|n| n.has_tag_name("Server")) {
+        let child_text = |tag: &str| {
+            server
+                .children()
+                .find(|n| n.has_tag_name(tag))
+                .and_then(|n| n.text())
+                .map(|t| t.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let host = child_text("Host");
+        let name = Some(child_text("Name"))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| host.clone());
+
+        if host.is_empty() {
+            report.skip(&name, "호스트가 없습니다");
+            continue;
+        }
+
+        // 0: FTP, 1: SFTP, 6: 암호화 없는 FTP. FTPS 계열(3, 4)은 지원하지 않는다
+        let protocol = match child_text("Protocol").as_str() {
+            "" | "0" | "6" => "ftp",
+            "1" => "sftp",
+            other => {
+                report.skip(&name, format!("지원하지 않는 프로토콜 ({})", other));
+                continue;
+            }
+        };
+
+        let port = child_text("Port")
+            .parse()
+            .unwrap_or_else(|_| default_port(protocol));
+
+        let password = match server.children().find(|n| n.has_tag_name("Pass")) {
+            Some(pass) => {
+                let text = pass.text().unwrap_or("").trim();
+                match pass.attribute("encoding") {
+                    Some("base64") => match STANDARD.decode(text) {
+                        Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+                        Err(_) => {
+                            report.skip(&name, "비밀번호 디코딩 실패");
+                            continue;
+                        }
+                    },
+                    Some("crypt") => {
+                        report.skip(&name, "마스터 비밀번호로 암호화된 비밀번호");
+                        continue;
+                    }
+                    _ => text.to_string(),
+                }
+            }
+            None => String::new(),
+        };
+
+        report.imported.push(new_connection(
+            &name,
+            protocol,
+            &host,
+            port,
+            &child_text("User"),
+            &password,
+        ));
+    }
+
+    Ok(report)
+}
+
+pub fn import_cyberduck(file_path: &str) -> Result<ImportReport, String> {
+    let path = Path::new(file_path);
+    let mut report = ImportReport::default();
+
+    if path.is_dir() {
+        let entries = fs::read_dir(path).map_err(|e| format!("디렉토리 읽기 실패: {}", e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("항목 읽기 실패: {}", e))?;
+            let entry_path = entry.path();
+            if entry_path.extension().and_then(|e| e.to_str()) == Some("duck") {
+                import_duck_file(&entry_path, &mut report);
+            }
+        }
+    } else {
+        import_duck_file(path, &mut report);
+    }
+
+    Ok(report)
+}
+
+fn import_duck_file(path: &Path, report: &mut ImportReport) {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            report.skip(&file_name, format!("파일 읽기 실패: {}", e));
+            return;
+        }
+    };
+
+    // Cyberduck이 저장한 파일은 plist DOCTYPE 선언으로 시작한다
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let doc = match roxmltree::Document::parse_with_options(&content, options) {
+        Ok(doc) => doc,
+        Err(e) => {
+            report.skip(&file_name, format!("plist 파싱 실패: {}", e));
+            return;
+        }
+    };
+
+    let Some(dict) = doc.descendants().find(|n| n.has_tag_name("dict")) else {
+        report.skip(&file_name, "plist에 dict가 없습니다");
+        return;
+    };
+
+    let value_of = |key: &str| {
+        let mut elements = dict.children().filter(|n| n.is_element());
+        while let Some(node) = elements.next() {
+            if node.has_tag_name("key") && node.text() == Some(key) {
+                return elements
+                    .next()
+                    .and_then(|v| v.text())
+                    .map(|t| t.trim().to_string())
+                    .unwrap_or_default();
+            }
+        }
+        String::new()
+    };
+
+    let host = value_of("Hostname");
+    let name = Some(value_of("Nickname"))
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| host.clone());
+
+    if host.is_empty() {
+        report.skip(&name, "호스트가 없습니다");
+        return;
+    }
+
+    let protocol = match value_of("Protocol").as_str() {
+        "ftp" => "ftp",
+        "sftp" => "sftp",
+        "smb" => "smb",
+        other => {
+            report.skip(&name, format!("지원하지 않는 프로토콜 ({})", other));
+            return;
+        }
+    };
+
+    let port = value_of("Port")
+        .parse()
+        .unwrap_or_else(|_| default_port(protocol));
+
+    // Cyberduck은 비밀번호를 키체인에 보관하므로 북마크 파일에는 없다
+    let mut conn = new_connection(&name, protocol, &host, port, &value_of("Username"), "");
+
+    if protocol == "smb" {
+        let path = value_of("Path");
+        let share = path.trim_start_matches('/').split('/').next().unwrap_or("");
+        if share.is_empty() {
+            report.skip(&name, "SMB 공유폴더 이름이 없습니다");
+            return;
+        }
+        conn.share = Some(share.to_string());
+    }
+
+    report.imported.push(conn);
+}
+
+pub fn import_winscp(file_path: &str) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(file_path).map_err(|e| format!("파일 읽기 실패: {}", e))?;
+
+    let mut report = ImportReport::default();
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((section.to_string(), Vec::new()));
+        } else if let (Some((key, value)), Some((_, values))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            values.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    for (section, values) in sections {
+        let Some(session) = section.strip_prefix("Sessions\\") else {
+            continue;
+        };
+
+        let session = urlencoding::decode(session)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|_| session.to_string());
+        if session == "Default Settings" {
+            continue;
+        }
+
+        // 폴더 경로(a/b/name)는 마지막 이름만 사용
+        let name = session.rsplit('/').next().unwrap_or(&session).to_string();
+
+        let value_of = |key: &str| {
+            values
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default()
+        };
+
+        let host = value_of("HostName");
+        if host.is_empty() {
+            report.skip(&name, "호스트가 없습니다");
+            continue;
+        }
+
+        // FSProtocol 0: SCP, 1/2: SFTP, 5: FTP (기본값은 SFTP)
+        let protocol = match value_of("FSProtocol").as_str() {
+            "" | "0" | "1" | "2" => "sftp",
+            "5" if value_of("Ftps").is_empty() || value_of("Ftps") == "0" => "ftp",
+            "5" => {
+                report.skip(&name, "FTPS는 지원하지 않습니다");
+                continue;
+            }
+            other => {
+                report.skip(&name, format!("지원하지 않는 프로토콜 ({})", other));
+                continue;
+            }
+        };
+
+        let port = value_of("PortNumber")
+            .parse()
+            .unwrap_or_else(|_| default_port(protocol));
+        let username = urlencoding::decode(&value_of("UserName"))
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+
+        let stored_password = value_of("Password");
+        let password = if stored_password.is_empty() {
+            String::new()
+        } else {
+            match decrypt_winscp_password(&host, &username, &stored_password) {
+                Some(password) => password,
+                None => {
+                    report.skip(&name, "비밀번호 복호화 실패");
+                    continue;
+                }
+            }
+        };
+
+        report
+            .imported
+            .push(new_connection(&name, protocol, &host, port, &username, &password));
+    }
+
+    Ok(report)
+}
+
+fn decrypt_winscp_password(host: &str, username: &str, encrypted: &str) -> Option<String> {
+    const MAGIC: u8 = 0xA3;
+    const FLAG: u8 = 0xFF;
+
+    let digits: Vec<u8> = encrypted
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    let mut bytes = digits
+        .chunks(2)
+        .filter(|pair| pair.len() == 2)
+        .map(|pair| !(((pair[0] << 4) | pair[1]) ^ MAGIC));
+
+    let flag = bytes.next()?;
+    let length = if flag == FLAG {
+        bytes.next()?;
+        bytes.next()?
+    } else {
+        flag
+    };
+
+    let skip = bytes.next()? as usize;
+    let decrypted: Vec<u8> = bytes.skip(skip).take(length as usize).collect();
+    if decrypted.len() != length as usize {
+        return None;
+    }
+
+    let decrypted = String::from_utf8_lossy(&decrypted).to_string();
+
+    if flag == FLAG {
+        let key = format!("{}{}", username, host);
+        decrypted.strip_prefix(&key).map(|p| p.to_string())
+    } else {
+        Some(decrypted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!(
+            "{}/tests/fixtures/bookmarks/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        )
+    }
+
+    fn skipped_names(report: &ImportReport) -> Vec<&str> {
+        report.skipped.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn assert_unique_ids(report: &ImportReport) {
+        let mut ids: Vec<&str> = report.imported.iter().map(|c| c.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), report.imported.len());
+    }
+
+    #[test]
+    fn imports_filezilla_site_manager() {
+        let report = import_filezilla(&fixture("filezilla.xml")).unwrap();
+
+        assert_eq!(report.imported.len(), 2);
+        let ftp = &report.imported[0];
+        assert_eq!(ftp.name, "Work FTP");
+        assert_eq!(ftp.protocol, "ftp");
+        assert_eq!(ftp.port, 2121);
+        assert_eq!(ftp.username, "alice");
+        assert_eq!(ftp.password, "secret");
+
+        let sftp = &report.imported[1];
+        assert_eq!(sftp.name, "sftp.example.com");
+        assert_eq!(sftp.protocol, "sftp");
+        assert_eq!(sftp.port, 22);
+
+        assert_eq!(
+            skipped_names(&report),
+            ["Explicit FTPS", "Master password", "No host"]
+        );
+        assert_unique_ids(&report);
+    }
+
+    #[test]
+    fn imports_cyberduck_bookmark_folder() {
+        let mut report = import_cyberduck(&fixture("cyberduck")).unwrap();
+        report.imported.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(report.imported.len(), 2);
+        let sftp = &report.imported[0];
+        assert_eq!(sftp.name, "Build Server");
+        assert_eq!(sftp.protocol, "sftp");
+        assert_eq!(sftp.port, 2222);
+        assert_eq!(sftp.username, "ci");
+        assert!(sftp.password.is_empty());
+
+        let smb = &report.imported[1];
+        assert_eq!(smb.name, "nas.local");
+        assert_eq!(smb.port, 445);
+        assert_eq!(smb.share.as_deref(), Some("media"));
+
+        assert_eq!(skipped_names(&report), ["Bucket"]);
+        assert_unique_ids(&report);
+    }
+
+    #[test]
+    fn imports_single_cyberduck_file() {
+        let report = import_cyberduck(&fixture("cyberduck/sftp.duck")).unwrap();
+        assert_eq!(report.imported.len(), 1);
+        assert_eq!(report.imported[0].host, "build.example.com");
+    }
+
+    #[test]
+    fn imports_winscp_ini() {
+        let report = import_winscp(&fixture("winscp.ini")).unwrap();
+
+        assert_eq!(report.imported.len(), 2);
+        let deploy = &report.imported[0];
+        assert_eq!(deploy.name, "Deploy Box");
+        assert_eq!(deploy.protocol, "sftp");
+        assert_eq!(deploy.port, 2200);
+        assert_eq!(deploy.username, "deploy");
+        assert_eq!(deploy.password, "p@ss word");
+
+        let ftp = &report.imported[1];
+        assert_eq!(ftp.name, "Legacy FTP");
+        assert_eq!(ftp.protocol, "ftp");
+        assert_eq!(ftp.port, 21);
+
+        assert_eq!(skipped_names(&report), ["Secure FTP", "Broken Password"]);
+        assert_unique_ids(&report);
+    }
+}
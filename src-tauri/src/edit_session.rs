@@ -0,0 +1,228 @@
+use crate::{ActiveConnection, AppState};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+struct RemoteVersion {
+    size: u64,
+    modified: String,
+}
+
+impl RemoteVersion {
+    /// SIZE와 MDTM을 모두 지원하지 않는 FTP 서버에서는 크기와 시각을 알 수 없다
+    fn is_unknown(&self) -> bool {
+        self.size == 0 && self.modified.is_empty()
+    }
+}
+
+/// 에디터로 연 임시 파일과 그 원본 원격 파일
+pub struct EditedFile {
+    pub remote_path: String,
+    pub local_path: PathBuf,
+    baseline: RemoteVersion,
+    // 마지막으로 올린 로컬 파일 상태
+    local_seen: Option<(SystemTime, u64)>,
+    // 마지막으로 올리려고 시도한 로컬 파일 상태. 실패나 충돌을 매번 다시 알리지 않게 한다
+    local_attempted: Option<(SystemTime, u64)>,
+    conflict: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EditStatus {
+    Uploaded,
+    Conflict,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditSyncEvent {
+    pub remote_path: String,
+    pub local_path: String,
+    pub status: EditStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditedFileInfo {
+    pub remote_path: String,
+    pub local_path: String,
+    pub conflict: bool,
+}
+
+fn local_version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn remote_version(conn: &mut ActiveConnection, remote_path: &str) -> Result<RemoteVersion, String> {
+    let entry = crate::stat_remote(conn, remote_path)?;
+
+    Ok(RemoteVersion {
+        size: entry.size,
+        modified: entry.modified,
+    })
+}
+
+impl EditedFile {
+    pub fn new(
+        conn: &mut ActiveConnection,
+        remote_path: &str,
+        local_path: PathBuf,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            remote_path: remote_path.to_string(),
+            baseline: remote_version(conn, remote_path)?,
+            local_seen: local_version(&local_path),
+            local_attempted: None,
+            local_path,
+            conflict: false,
+        })
+    }
+
+    pub fn has_local_changes(&self) -> bool {
+        local_version(&self.local_path).is_some_and(|v| Some(v) != self.local_seen)
+    }
+
+    /// 아직 올리려고 시도하지 않은 저장이 있는지 확인한다
+    fn has_new_local_changes(&self) -> bool {
+        local_version(&self.local_path)
+            .is_some_and(|v| Some(v) != self.local_seen && Some(v) != self.local_attempted)
+    }
+
+    pub fn info(&self) -> EditedFileInfo {
+        EditedFileInfo {
+            remote_path: self.remote_path.clone(),
+            local_path: self.local_path.to_string_lossy().to_string(),
+            conflict: self.conflict,
+        }
+    }
+
+    fn try_sync(
+        &mut self,
+        conn: &mut ActiveConnection,
+        force: bool,
+    ) -> Result<(EditStatus, Option<String>), String> {
+        let local = local_version(&self.local_path);
+        self.local_attempted = local;
+        let current = remote_version(conn, &self.remote_path)?;
+
+        // 편집하는 동안 서버의 파일이 바뀌었으면 덮어쓰지 않고 알린다
+        if !force && current != self.baseline {
+            self.conflict = true;
+            return Ok((
+                EditStatus::Conflict,
+                Some("편집하는 동안 서버의 파일이 변경되었습니다".to_string()),
+            ));
+        }
+
+        let local_path = self.local_path.to_string_lossy().to_string();
+        match conn {
+            ActiveConnection::Ftp(ftp) => ftp.upload(&local_path, &self.remote_path)?,
+            ActiveConnection::Sftp(sftp) => sftp.upload(&local_path, &self.remote_path)?,
+            ActiveConnection::Smb(smb) => smb.upload(&local_path, &self.remote_path)?,
+            ActiveConnection::None => return Err("Not connected".to_string()),
+        }
+
+        // 올린 뒤에만 기록해야 실패한 저장을 세션을 닫을 때 다시 올린다
+        self.local_seen = local;
+        self.conflict = false;
+        let message = (!force && current.is_unknown()).then(|| {
+            "서버가 SIZE/MDTM을 지원하지 않아 서버 쪽 변경 여부를 확인하지 못했습니다".to_string()
+        });
+        self.baseline = remote_version(conn, &self.remote_path)?;
+        Ok((EditStatus::Uploaded, message))
+    }
+
+    /// 임시 파일을 원격에 올린다. force가 아니면 원격 변경 여부를 먼저 확인한다.
+    pub fn sync(&mut self, conn: &mut ActiveConnection, force: bool) -> EditSyncEvent {
+        let (status, message) = match self.try_sync(conn, force) {
+            Ok(result) => result,
+            Err(e) => (EditStatus::Failed, Some(e)),
+        };
+
+        EditSyncEvent {
+            remote_path: self.remote_path.clone(),
+            local_path: self.local_path.to_string_lossy().to_string(),
+            status,
+            message,
+        }
+    }
+
+    pub fn remove_temp_file(&self) {
+        let _ = std::fs::remove_file(&self.local_path);
+    }
+}
+
+/// 열린 편집 파일의 저장을 감지해 원래 세션으로 다시 올린다.
+/// 편집 중인 파일이 없어지면 스스로 종료한다.
+pub fn spawn_watcher(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let state = app.state::<AppState>();
+
+        let changed: Vec<PathBuf> = {
+            let Ok(edits) = state.edits.lock() else {
+                break;
+            };
+            if edits.is_empty() {
+                // edits를 잡은 채로 내려야 새로 등록된 파일을 놓치지 않는다
+                state.edit_watcher_running.store(false, Ordering::SeqCst);
+                break;
+            }
+            edits
+                .values()
+                .filter(|e| e.has_new_local_changes())
+                .map(|e| e.local_path.clone())
+                .collect()
+        };
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        let Ok(mut conn_guard) = state.connection.lock() else {
+            break;
+        };
+        let Ok(mut edits) = state.edits.lock() else {
+            break;
+        };
+
+        // 세션이 닫히면 편집 목록도 비워지므로, 남아 있는 항목은 현재 세션의 것이다
+        for local_path in changed {
+            if let Some(edit) = edits.get_mut(&local_path) {
+                let event = edit.sync(&mut conn_guard, false);
+                if matches!(event.status, EditStatus::Uploaded) {
+                    if let Ok(mut listing) = state.listing.lock() {
+                        listing.invalidate(&event.remote_path);
+                    }
+                }
+                let _ = app.emit("edit-sync", event);
+            }
+        }
+    });
+}
+
+/// 세션을 닫기 전에 저장되지 않은 변경을 올리고 임시 파일을 정리한다
+pub fn close_session(state: &AppState, app: &AppHandle) -> Result<(), String> {
+    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+    let mut edits = state.edits.lock().map_err(|e| e.to_string())?;
+
+    for (_, mut edit) in edits.drain() {
+        if edit.has_local_changes() {
+            let event = edit.sync(&mut conn_guard, false);
+            let _ = app.emit("edit-sync", event);
+        }
+        edit.remove_temp_file();
+    }
+
+    Ok(())
+}
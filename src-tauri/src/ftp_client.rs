@@ -1,62 +1,689 @@
 use crate::FileEntry;
 use std::fs::File;
-use std::io::{Read, Write};
-use suppaftp::FtpStream;
+use chrono::Datelike;
+use encoding_rs::{Encoding, UTF_8};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use suppaftp::types::{FileType, FormatControl};
+use suppaftp::{FtpStream, Mode, Status};
+
+pub struct FtpOptions {
+    pub ascii: bool,
+    pub mode: Mode,
+    pub nat_workaround: bool,
+    pub timeout: Duration,
+    pub charset: Option<String>,
+}
+
+pub fn parse_mode(value: Option<&str>) -> Mode {
+    match value {
+        Some("active") => Mode::Active,
+        Some("epsv") => Mode::ExtendedPassive,
+        _ => Mode::Passive,
+    }
+}
 
 pub struct FtpConnection {
     stream: FtpStream,
+    // None이면 UTF-8, 그 외에는 파일 이름을 해당 문자셋으로 변환한다
+    encoding: Option<&'static Encoding>,
+    // suppaftp 대신 직접 명령을 보내고 데이터 연결을 연다 (문자셋 변환, IPv6 액티브 모드)
+    raw: bool,
+    // 직접 보낸 명령의 응답을 읽는 버퍼. 매번 새로 만들면 미리 읽어 둔 응답을 잃는다
+    control: BufReader<TcpStream>,
+    mode: Mode,
+    nat_workaround: bool,
+    timeout: Duration,
+}
+
+fn negotiate_utf8(stream: &mut FtpStream) -> bool {
+    let features = match stream.custom_command("FEAT", &[Status::System]) {
+        Ok(response) => String::from_utf8_lossy(&response.body).to_uppercase(),
+        Err(_) => return false,
+    };
+
+    if !features.lines().any(|line| line.trim() == "UTF8") {
+        return false;
+    }
+
+    stream
+        .custom_command(
+            "OPTS UTF8 ON",
+            &[Status::CommandOk, Status::CommandNotImplemented],
+        )
+        .is_ok()
+}
+
+/// 액티브 모드에서는 명령을 보낸 뒤에 서버가 접속해 오므로 연결을 나중에 받는다
+enum DataChannel {
+    Connected(TcpStream),
+    Listening(TcpListener),
+}
+
+impl DataChannel {
+    fn accept(self, timeout: Duration) -> Result<TcpStream, String> {
+        let listener = match self {
+            DataChannel::Connected(stream) => return Ok(stream),
+            DataChannel::Listening(listener) => listener,
+        };
+
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("데이터 연결 대기 실패: {}", e))?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    stream
+                        .set_nonblocking(false)
+                        .map_err(|e| format!("데이터 연결 설정 실패: {}", e))?;
+                    return Ok(stream);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Err("서버가 데이터 연결을 열지 않았습니다".to_string());
+                    }
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                Err(e) => return Err(format!("데이터 연결 수락 실패: {}", e)),
+            }
+        }
+    }
+}
+
+/// PORT는 IPv4 주소만 담을 수 있어 IPv6에서는 EPRT(RFC 2428)를 사용한다
+fn port_command(addr: SocketAddr) -> (&'static str, String) {
+    match addr.ip() {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            let port = addr.port();
+            (
+                "PORT",
+                format!("{},{},{},{},{},{}", a, b, c, d, port >> 8, port & 0xff),
+            )
+        }
+        IpAddr::V6(ip) => ("EPRT", format!("|2|{}|{}|", ip, addr.port())),
+    }
+}
+
+fn find_encoding(charset: Option<&str>) -> Result<Option<&'static Encoding>, String> {
+    let label = match charset.map(|c| c.trim()) {
+        None | Some("") | Some("auto") => return Ok(None),
+        Some(label) if label.eq_ignore_ascii_case("cp949") => "windows-949",
+        Some(label) => label,
+    };
+
+    let encoding = Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| format!("지원하지 않는 문자셋: {}", label))?;
+
+    Ok(Some(encoding).filter(|e| *e != UTF_8))
+}
+
+#[derive(Debug, PartialEq)]
+enum ListKind {
+    File,
+    Directory,
+    Link,
+}
+
+#[derive(Debug)]
+struct ListItem {
+    name: String,
+    kind: ListKind,
+    size: u64,
+    modified: String,
+    permissions: Option<String>,
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// 공백으로 나눈 단어와 줄 안에서의 시작 위치. 파일 이름의 공백을 보존하기 위해 위치를 함께 둔다.
+fn split_columns(line: &str) -> Vec<(usize, &str)> {
+    let mut columns = Vec::new();
+    let mut start = None;
+    for (index, ch) in line.char_indices() {
+        match (ch.is_whitespace(), start) {
+            (true, Some(begin)) => {
+                columns.push((begin, &line[begin..index]));
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        columns.push((begin, &line[begin..]));
+    }
+    columns
+}
+
+/// rwxr-xr-x 형식을 755 같은 8진수 문자열로 바꾼다
+fn permission_octal(flags: &str) -> Option<String> {
+    let flags = flags.as_bytes().get(1..10)?;
+    let mut mode = 0u32;
+    for (index, flag) in flags.iter().enumerate() {
+        if !matches!(flag, b'-' | b'r' | b'w' | b'x' | b's' | b'S' | b't' | b'T') {
+            return None;
+        }
+        if !matches!(flag, b'-' | b'S' | b'T') {
+            mode |= 1 << (8 - index);
+        }
+    }
+    Some(format!("{:o}", mode))
+}
+
+/// ls -l 형식: drwxr-xr-x 2 owner group 4096 Mar  5 14:30 name
+fn parse_unix_line(line: &str, today: chrono::NaiveDate) -> Option<ListItem> {
+    let columns = split_columns(line);
+    let flags = columns.first()?.1;
+    let kind = match flags.chars().next()? {
+        'd' => ListKind::Directory,
+        'l' => ListKind::Link,
+        '-' => ListKind::File,
+        _ => return None,
+    };
+    if flags.len() < 10 {
+        return None;
+    }
+
+    // 그룹이 빠진 서버도 있으므로 월 이름을 기준으로 위치를 찾는다
+    let month_index = (3..columns.len().saturating_sub(3)).find(|&i| {
+        MONTHS.contains(&columns[i].1.to_ascii_lowercase().as_str())
+            && columns[i - 1].1.parse::<u64>().is_ok()
+    })?;
+    let size = columns[month_index - 1].1.parse().ok()?;
+    let month = MONTHS
+        .iter()
+        .position(|m| *m == columns[month_index].1.to_ascii_lowercase())? as u32
+        + 1;
+    let day: u32 = columns[month_index + 1].1.parse().ok()?;
+    let time_or_year = columns[month_index + 2].1;
+
+    // 최근 파일은 연도 대신 시각이 나오고, 미래 날짜라면 작년 파일이다
+    let modified = match time_or_year.split_once(':') {
+        Some((hour, minute)) => {
+            let time =
+                chrono::NaiveTime::from_hms_opt(hour.parse().ok()?, minute.parse().ok()?, 0)?;
+            let mut date = chrono::NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            if date > today + chrono::Duration::days(1) {
+                date = chrono::NaiveDate::from_ymd_opt(today.year() - 1, month, day)?;
+            }
+            date.and_time(time)
+        }
+        None => chrono::NaiveDate::from_ymd_opt(time_or_year.parse().ok()?, month, day)?
+            .and_hms_opt(0, 0, 0)?,
+    };
+
+    let name_start = columns.get(month_index + 3)?.0;
+    let mut name = &line[name_start..];
+    if kind == ListKind::Link {
+        name = name.split(" -> ").next().unwrap_or(name);
+    }
+
+    Some(ListItem {
+        name: name.to_string(),
+        kind,
+        size,
+        modified: modified.format("%Y-%m-%d %H:%M:%S").to_string(),
+        permissions: permission_octal(flags),
+    })
+}
+
+/// IIS 등의 DOS 형식: 03-05-24  02:30PM       <DIR>          name
+fn parse_dos_line(line: &str) -> Option<ListItem> {
+    let columns = split_columns(line);
+    if columns.len() < 4 {
+        return None;
+    }
+
+    let date = chrono::NaiveDate::parse_from_str(columns[0].1, "%m-%d-%y")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(columns[0].1, "%m-%d-%Y"))
+        .ok()?;
+    let time = chrono::NaiveTime::parse_from_str(columns[1].1, "%I:%M%p")
+        .or_else(|_| chrono::NaiveTime::parse_from_str(columns[1].1, "%H:%M"))
+        .ok()?;
+    let (kind, size) = match columns[2].1 {
+        "<DIR>" => (ListKind::Directory, 0),
+        size => (ListKind::File, size.parse().ok()?),
+    };
+
+    Some(ListItem {
+        name: line[columns[3].0..].to_string(),
+        kind,
+        size,
+        modified: date.and_time(time).format("%Y-%m-%d %H:%M:%S").to_string(),
+        permissions: None,
+    })
+}
+
+/// LIST 응답 한 줄을 해석한다. "total 12" 같은 줄이나 알 수 없는 형식은 None.
+fn parse_list_line(line: &str, today: chrono::NaiveDate) -> Option<ListItem> {
+    parse_unix_line(line, today).or_else(|| parse_dos_line(line))
 }
 
 impl FtpConnection {
-    pub fn connect(host: &str, port: u16, username: &str, password: &str) -> Result<Self, String> {
-        let addr = format!("{}:{}", host, port);
-        let mut stream = FtpStream::connect(&addr).map_err(|e| format!("FTP 연결 실패: {}", e))?;
+    pub fn connect(
+        host: &str,
+        port: u16,
+        username: &str,
+        password: &str,
+        options: &FtpOptions,
+    ) -> Result<Self, String> {
+        let addr = format!("{}:{}", host, port)
+            .to_socket_addrs()
+            .map_err(|e| format!("FTP 주소 확인 실패: {}", e))?
+            .next()
+            .ok_or("FTP 주소를 찾을 수 없습니다")?;
+        let mut stream = FtpStream::connect_timeout(addr, options.timeout)
+            .map_err(|e| format!("FTP 연결 실패: {}", e))?;
+
+        let _ = stream.get_ref().set_read_timeout(Some(options.timeout));
+        let _ = stream.get_ref().set_write_timeout(Some(options.timeout));
 
         stream
             .login(username, password)
             .map_err(|e| format!("FTP 로그인 실패: {}", e))?;
 
-        Ok(Self { stream })
+        // PASV 응답은 IPv4 주소만 담을 수 있으므로 IPv6에서는 EPSV를 사용한다
+        let mode = match options.mode {
+            Mode::Passive if addr.is_ipv6() => Mode::ExtendedPassive,
+            mode => mode,
+        };
+        stream.set_mode(mode);
+
+        // NAT 뒤의 서버가 사설 IP를 알려주는 경우 제어 연결의 호스트로 접속한다
+        stream.set_passive_nat_workaround(options.nat_workaround);
+
+        let file_type = if options.ascii {
+            FileType::Ascii(FormatControl::Default)
+        } else {
+            FileType::Binary
+        };
+        stream
+            .transfer_type(file_type)
+            .map_err(|e| format!("전송 모드 설정 실패: {}", e))?;
+
+        // 문자셋을 직접 지정했으면 그대로 쓰고, 지정하지 않았을 때만 서버와 UTF8을 협상한다
+        let encoding = find_encoding(options.charset.as_deref())?;
+        if encoding.is_none() {
+            negotiate_utf8(&mut stream);
+        }
+
+        // suppaftp의 액티브 모드는 PORT만 보내므로 IPv6에서는 EPRT를 직접 보낸다
+        let raw = encoding.is_some() || (mode == Mode::Active && addr.is_ipv6());
+        let control = stream
+            .get_ref()
+            .try_clone()
+            .map(BufReader::new)
+            .map_err(|e| format!("FTP 연결 설정 실패: {}", e))?;
+
+        Ok(Self {
+            stream,
+            encoding,
+            raw,
+            control,
+            mode,
+            nat_workaround: options.nat_workaround,
+            timeout: options.timeout,
+        })
     }
 
-    pub fn list_files(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
-        let path = if path.is_empty() { "/" } else { path };
+    fn encode(&self, text: &str) -> Vec<u8> {
+        match self.encoding {
+            Some(encoding) => encoding.encode(text).0.into_owned(),
+            None => text.as_bytes().to_vec(),
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        match self.encoding {
+            Some(encoding) => encoding.decode(bytes).0.into_owned(),
+            None => String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
 
-        let list = self
+    fn send_raw(&self, command: &str, argument: Option<&str>) -> Result<(), String> {
+        let mut line = command.as_bytes().to_vec();
+        if let Some(argument) = argument {
+            line.push(b' ');
+            line.extend(self.encode(argument));
+        }
+        line.extend_from_slice(b"\r\n");
+
+        let mut control = self.stream.get_ref();
+        control
+            .write_all(&line)
+            .map_err(|e| format!("FTP 명령 전송 실패: {}", e))
+    }
+
+    fn read_raw_line(&mut self) -> Result<Vec<u8>, String> {
+        let mut line = Vec::new();
+        match self.control.read_until(b'\n', &mut line) {
+            Ok(0) => Err("FTP 연결이 종료되었습니다".to_string()),
+            Ok(_) => Ok(line),
+            Err(e) => Err(format!("FTP 응답 읽기 실패: {}", e)),
+        }
+    }
+
+    fn read_raw_response(&mut self, expected: &[u32]) -> Result<String, String> {
+        let mut multiline_code: Option<u32> = None;
+
+        loop {
+            let line = self.read_raw_line()?;
+            if line.len() < 4 || !line[..3].iter().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+
+            let code: u32 = String::from_utf8_lossy(&line[..3]).parse().unwrap_or(0);
+            match (line[3], multiline_code) {
+                (b'-', None) => {
+                    multiline_code = Some(code);
+                    continue;
+                }
+                (b' ', None) => {}
+                (b' ', Some(first)) if first == code => {}
+                _ => continue,
+            }
+
+            let message = self.decode(&line[4..]).trim_end().to_string();
+            if expected.contains(&code) {
+                return Ok(message);
+            }
+            return Err(format!("{} {}", code, message));
+        }
+    }
+
+    fn raw_command(
+        &mut self,
+        command: &str,
+        argument: Option<&str>,
+        expected: &[u32],
+    ) -> Result<String, String> {
+        self.send_raw(command, argument)?;
+        self.read_raw_response(expected)
+    }
+
+    fn open_data_stream(&mut self) -> Result<DataChannel, String> {
+        if self.mode == Mode::Active {
+            let local = self
+                .stream
+                .get_ref()
+                .local_addr()
+                .map_err(|e| format!("FTP 연결 정보 확인 실패: {}", e))?;
+            let listener = TcpListener::bind(SocketAddr::new(local.ip(), 0))
+                .map_err(|e| format!("데이터 연결 대기 실패: {}", e))?;
+            let addr = listener
+                .local_addr()
+                .map_err(|e| format!("데이터 연결 대기 실패: {}", e))?;
+
+            let (command, argument) = port_command(addr);
+            self.raw_command(command, Some(&argument), &[200])?;
+            return Ok(DataChannel::Listening(listener));
+        }
+
+        let peer = self
             .stream
-            .nlst(Some(path))
+            .get_ref()
+            .peer_addr()
+            .map_err(|e| format!("FTP 연결 정보 확인 실패: {}", e))?;
+
+        let addr = if self.mode == Mode::ExtendedPassive || peer.is_ipv6() {
+            // 229 Entering Extended Passive Mode (|||port|)
+            let message = self.raw_command("EPSV", None, &[229])?;
+            let port = message
+                .split('|')
+                .nth(3)
+                .and_then(|p| p.parse::<u16>().ok())
+                .ok_or("EPSV 응답을 해석할 수 없습니다")?;
+            SocketAddr::new(peer.ip(), port)
+        } else {
+            // 227 Entering Passive Mode (h1,h2,h3,h4,p1,p2)
+            let message = self.raw_command("PASV", None, &[227])?;
+            let start = message.find('(').ok_or("PASV 응답을 해석할 수 없습니다")?;
+            let end = message.rfind(')').ok_or("PASV 응답을 해석할 수 없습니다")?;
+            let numbers: Vec<u8> = message[start + 1..end]
+                .split(',')
+                .filter_map(|n| n.trim().parse().ok())
+                .collect();
+            if numbers.len() != 6 {
+                return Err("PASV 응답을 해석할 수 없습니다".to_string());
+            }
+
+            let ip = if self.nat_workaround {
+                peer.ip()
+            } else {
+                IpAddr::V4(Ipv4Addr::new(numbers[0], numbers[1], numbers[2], numbers[3]))
+            };
+            SocketAddr::new(ip, u16::from(numbers[4]) << 8 | u16::from(numbers[5]))
+        };
+
+        let data = TcpStream::connect_timeout(&addr, self.timeout)
+            .map_err(|e| format!("데이터 연결 실패: {}", e))?;
+
+        Ok(DataChannel::Connected(data))
+    }
+
+    /// 데이터 연결을 준비하고 전송 명령을 보낸 뒤 연결된 스트림을 돌려준다
+    fn data_command(
+        &mut self,
+        command: &str,
+        argument: Option<&str>,
+        offset: u64,
+    ) -> Result<TcpStream, String> {
+        let channel = self.open_data_stream()?;
+        // REST는 전송 명령 바로 앞에 보내야 한다
+        if offset > 0 {
+            self.raw_command("REST", Some(&offset.to_string()), &[350])?;
+        }
+        self.raw_command(command, argument, &[125, 150])?;
+
+        let data = channel.accept(self.timeout)?;
+        let _ = data.set_read_timeout(Some(self.timeout));
+        let _ = data.set_write_timeout(Some(self.timeout));
+        Ok(data)
+    }
+
+    fn raw_retrieve(&mut self, command: &str, argument: Option<&str>) -> Result<Vec<u8>, String> {
+        let mut data = self.data_command(command, argument, 0)?;
+
+        let mut buffer = Vec::new();
+        data.read_to_end(&mut buffer)
+            .map_err(|e| format!("데이터 읽기 실패: {}", e))?;
+        drop(data);
+
+        self.read_raw_response(&[226, 250])?;
+        Ok(buffer)
+    }
+
+    fn raw_retrieve_range(
+        &mut self,
+        path: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, String> {
+        let mut data = self.data_command("RETR", Some(path), offset)?;
+
+        let mut buffer = Vec::new();
+        (&mut data)
+            .take(length + 1)
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("데이터 읽기 실패: {}", e))?;
+        drop(data);
+
+        // 중간에 데이터 연결을 끊으면 서버는 426/451로 응답한다
+        self.read_raw_response(&[226, 250, 426, 451])?;
+        buffer.truncate(length as usize);
+        Ok(buffer)
+    }
+
+    fn raw_read_with(
+        &mut self,
+        path: &str,
+        read: &mut dyn FnMut(&mut dyn Read) -> std::io::Result<()>,
+    ) -> Result<(), String> {
+        let mut data = self.data_command("RETR", Some(path), 0)?;
+
+        let result = read(&mut data);
+        drop(data);
+
+        // 읽다가 실패해 데이터 연결을 끊었다면 서버는 426/451로 응답한다
+        if result.is_ok() {
+            self.read_raw_response(&[226, 250])?;
+        } else {
+            self.read_raw_response(&[226, 250, 426, 451])?;
+        }
+        result.map_err(|e| format!("데이터 읽기 실패: {}", e))
+    }
+
+    fn raw_store(&mut self, path: &str, content: &[u8]) -> Result<(), String> {
+        let mut data = self.data_command("STOR", Some(path), 0)?;
+
+        data.write_all(content)
+            .map_err(|e| format!("데이터 쓰기 실패: {}", e))?;
+        drop(data);
+
+        self.read_raw_response(&[226, 250])?;
+        Ok(())
+    }
+
+    fn retrieve_lines(&mut self, command: &str, argument: &str) -> Result<Vec<String>, String> {
+        if !self.raw {
+            let result = match command {
+                "LIST" => self.stream.list(Some(argument)),
+                _ => self.stream.nlst(Some(argument)),
+            };
+            return result.map_err(|e| e.to_string());
+        }
+
+        let data = self.raw_retrieve(command, Some(argument))?;
+        Ok(self
+            .decode(&data)
+            .lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+
+    fn list(&mut self, path: &str, show_hidden: bool) -> Result<Vec<String>, String> {
+        // ls 기반 서버는 -a로 숨김 파일을 함께 돌려주지만, 옵션을 모르는 서버는
+        // 거부하거나 "-a"를 경로로 보고 빈 목록을 준다. 그럴 때는 옵션 없이 다시 요청한다.
+        if show_hidden {
+            match self.retrieve_lines("LIST", &format!("-a {}", path)) {
+                Ok(lines) if !lines.is_empty() => return Ok(lines),
+                _ => {}
+            }
+        }
+
+        self.retrieve_lines("LIST", path)
+    }
+
+    fn cwd(&mut self, path: &str) -> bool {
+        if !self.raw {
+            return self.stream.cwd(path).is_ok();
+        }
+
+        self.raw_command("CWD", Some(path), &[250]).is_ok()
+    }
+
+    fn size(&mut self, path: &str) -> u64 {
+        if !self.raw {
+            return self.stream.size(path).unwrap_or(0) as u64;
+        }
+
+        self.raw_command("SIZE", Some(path), &[213])
+            .ok()
+            .and_then(|size| size.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn modified(&mut self, path: &str) -> Option<String> {
+        let modified = if !self.raw {
+            self.stream.mdtm(path).ok()?
+        } else {
+            // 213 YYYYMMDDHHMMSS[.sss]
+            let message = self.raw_command("MDTM", Some(path), &[213]).ok()?;
+            let digits = message.trim().split('.').next()?;
+            chrono::NaiveDateTime::parse_from_str(digits, "%Y%m%d%H%M%S").ok()?
+        };
+
+        Some(modified.format("%Y-%m-%d %H:%M:%S").to_string())
+    }
+
+    fn retrieve(&mut self, path: &str) -> Result<Vec<u8>, String> {
+        if !self.raw {
+            return self
+                .stream
+                .retr_as_buffer(path)
+                .map(|data| data.into_inner())
+                .map_err(|e| e.to_string());
+        }
+
+        self.raw_retrieve("RETR", Some(path))
+    }
+
+    /// 목록의 각 항목을 해석하는 대로 넘긴다. 심볼릭 링크는 폴더인지 확인한 뒤에 넘긴다.
+    pub fn list_files_each(
+        &mut self,
+        path: &str,
+        show_hidden: bool,
+        on_entry: &mut dyn FnMut(FileEntry),
+    ) -> Result<(), String> {
+        let path = if path.is_empty() { "/" } else { path };
+
+        let lines = self
+            .list(path, show_hidden)
             .map_err(|e| format!("파일 목록 조회 실패: {}", e))?;
 
-        let mut entries = Vec::new();
+        let today = chrono::Local::now().date_naive();
+
+        for line in &lines {
+            let Some(item) = parse_list_line(line, today) else {
+                continue;
+            };
+            let name = item.name;
+            if name == "." || name == ".." || (!show_hidden && name.starts_with('.')) {
+                continue;
+            }
 
-        for name in list {
             let file_path = if path == "/" {
                 format!("/{}", name)
             } else {
                 format!("{}/{}", path.trim_end_matches('/'), name)
             };
 
-            let is_directory = self.stream.cwd(&file_path).is_ok();
-            if is_directory {
-                let _ = self.stream.cwd(path);
-            }
-
-            let size = if !is_directory {
-                self.stream.size(&file_path).unwrap_or(0) as u64
-            } else {
-                0
+            // 심볼릭 링크는 목록만으로는 대상이 폴더인지 알 수 없다
+            let is_directory = match item.kind {
+                ListKind::Directory => true,
+                ListKind::File => false,
+                ListKind::Link => {
+                    let is_directory = self.cwd(&file_path);
+                    if is_directory {
+                        self.cwd(path);
+                    }
+                    is_directory
+                }
             };
 
-            entries.push(FileEntry {
-                name: name.clone(),
+            on_entry(FileEntry {
+                name,
                 path: file_path,
                 is_directory,
-                size,
-                modified: String::new(),
-                permissions: None,
+                size: if is_directory { 0 } else { item.size },
+                modified: item.modified,
+                permissions: item.permissions,
             });
         }
 
+        Ok(())
+    }
+
+    pub fn list_files(&mut self, path: &str, show_hidden: bool) -> Result<Vec<FileEntry>, String> {
+        let mut entries = Vec::new();
+        self.list_files_each(path, show_hidden, &mut |entry| entries.push(entry))?;
+
         entries.sort_by(|a, b| {
             if a.is_directory == b.is_directory {
                 a.name.to_lowercase().cmp(&b.name.to_lowercase())
@@ -70,16 +697,30 @@ impl FtpConnection {
         Ok(entries)
     }
 
+    pub fn stat(&mut self, remote_path: &str) -> Result<FileEntry, String> {
+        let name = remote_path.rsplit('/').next().unwrap_or(remote_path).to_string();
+        let size = self.size(remote_path);
+        let modified = self.modified(remote_path).unwrap_or_default();
+
+        Ok(FileEntry {
+            name,
+            path: remote_path.to_string(),
+            is_directory: false,
+            size,
+            modified,
+            permissions: None,
+        })
+    }
+
     pub fn download(&mut self, remote_path: &str, local_path: &str) -> Result<(), String> {
         let data = self
-            .stream
-            .retr_as_buffer(remote_path)
+            .retrieve(remote_path)
             .map_err(|e| format!("다운로드 실패: {}", e))?;
 
         let mut file =
             File::create(local_path).map_err(|e| format!("파일 생성 실패: {}", e))?;
 
-        file.write_all(&data.into_inner())
+        file.write_all(&data)
             .map_err(|e| format!("파일 쓰기 실패: {}", e))?;
 
         Ok(())
@@ -93,6 +734,12 @@ impl FtpConnection {
         file.read_to_end(&mut buffer)
             .map_err(|e| format!("파일 읽기 실패: {}", e))?;
 
+        if self.raw {
+            return self
+                .raw_store(remote_path, &buffer)
+                .map_err(|e| format!("업로드 실패: {}", e));
+        }
+
         let mut reader = std::io::Cursor::new(buffer);
         self.stream
             .put_file(remote_path, &mut reader)
@@ -102,15 +749,93 @@ impl FtpConnection {
     }
 
     pub fn read_file(&mut self, remote_path: &str) -> Result<Vec<u8>, String> {
-        let data = self
+        self.retrieve(remote_path)
+            .map_err(|e| format!("파일 읽기 실패: {}", e))
+    }
+
+    /// 파일 전체를 메모리에 올리지 않고 데이터 연결을 그대로 넘긴다
+    pub fn read_with(
+        &mut self,
+        remote_path: &str,
+        read: &mut dyn FnMut(&mut dyn Read) -> std::io::Result<()>,
+    ) -> Result<(), String> {
+        if self.raw {
+            return self
+                .raw_read_with(remote_path, read)
+                .map_err(|e| format!("파일 읽기 실패: {}", e));
+        }
+
+        let mut data = self
+            .stream
+            .retr_as_stream(remote_path)
+            .map_err(|e| format!("파일 읽기 실패: {}", e))?;
+
+        if let Err(e) = read(&mut data) {
+            let _ = self.stream.abort(data);
+            return Err(format!("파일 읽기 실패: {}", e));
+        }
+
+        self.stream
+            .finalize_retr_stream(data)
+            .map_err(|e| format!("파일 읽기 실패: {}", e))
+    }
+
+    pub fn read_range(
+        &mut self,
+        remote_path: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, String> {
+        if self.raw {
+            return self
+                .raw_retrieve_range(remote_path, offset, length)
+                .map_err(|e| format!("파일 읽기 실패: {}", e));
+        }
+
+        if offset > 0 {
+            self.stream
+                .resume_transfer(offset as usize)
+                .map_err(|e| format!("파일 읽기 실패: {}", e))?;
+        }
+
+        let mut data = self
             .stream
-            .retr_as_buffer(remote_path)
+            .retr_as_stream(remote_path)
+            .map_err(|e| format!("파일 읽기 실패: {}", e))?;
+
+        // 한 바이트 더 읽어 봐서 파일이 남아 있을 때만 전송을 중단한다
+        let mut buffer = Vec::new();
+        (&mut data)
+            .take(length + 1)
+            .read_to_end(&mut buffer)
             .map_err(|e| format!("파일 읽기 실패: {}", e))?;
 
-        Ok(data.into_inner())
+        if buffer.len() as u64 > length {
+            buffer.truncate(length as usize);
+            self.stream
+                .abort(data)
+                .map_err(|e| format!("전송 중단 실패: {}", e))?;
+        } else {
+            self.stream
+                .finalize_retr_stream(data)
+                .map_err(|e| format!("파일 읽기 실패: {}", e))?;
+        }
+
+        Ok(buffer)
     }
 
     pub fn delete(&mut self, remote_path: &str, is_directory: bool) -> Result<(), String> {
+        if self.raw {
+            let (command, label) = if is_directory {
+                ("RMD", "폴더 삭제 실패")
+            } else {
+                ("DELE", "파일 삭제 실패")
+            };
+            self.raw_command(command, Some(remote_path), &[250])
+                .map_err(|e| format!("{}: {}", label, e))?;
+            return Ok(());
+        }
+
         if is_directory {
             self.stream
                 .rmdir(remote_path)
@@ -122,6 +847,31 @@ impl FtpConnection {
         }
         Ok(())
     }
+
+    pub fn create_dir(&mut self, remote_path: &str) -> Result<(), String> {
+        if self.raw {
+            self.raw_command("MKD", Some(remote_path), &[257])
+                .map_err(|e| format!("폴더 생성 실패: {}", e))?;
+            return Ok(());
+        }
+
+        self.stream
+            .mkdir(remote_path)
+            .map_err(|e| format!("폴더 생성 실패: {}", e))
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        if self.raw {
+            self.raw_command("RNFR", Some(from), &[350])
+                .and_then(|_| self.raw_command("RNTO", Some(to), &[250]))
+                .map_err(|e| format!("이름 변경 실패: {}", e))?;
+            return Ok(());
+        }
+
+        self.stream
+            .rename(from, to)
+            .map_err(|e| format!("이름 변경 실패: {}", e))
+    }
 }
 
 impl Drop for FtpConnection {
@@ -129,3 +879,85 @@ impl Drop for FtpConnection {
         let _ = self.stream.quit();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_command_uses_port_for_ipv4() {
+        let addr: SocketAddr = "192.168.0.10:50021".parse().unwrap();
+        assert_eq!(
+            port_command(addr),
+            ("PORT", "192,168,0,10,195,101".to_string())
+        );
+    }
+
+    fn today() -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(2024, 6, 15).unwrap()
+    }
+
+    #[test]
+    fn parses_unix_list_lines() {
+        let item = parse_list_line(
+            "-rw-r--r--    1 owner    group        1234 Mar  5 14:30 my file.txt",
+            today(),
+        )
+        .unwrap();
+        assert_eq!(item.name, "my file.txt");
+        assert_eq!(item.kind, ListKind::File);
+        assert_eq!(item.size, 1234);
+        assert_eq!(item.modified, "2024-03-05 14:30:00");
+        assert_eq!(item.permissions.as_deref(), Some("644"));
+
+        let item =
+            parse_list_line("drwxr-xr-x 2 owner 4096 Dec 31  2020 .config", today()).unwrap();
+        assert_eq!(item.name, ".config");
+        assert_eq!(item.kind, ListKind::Directory);
+        assert_eq!(item.modified, "2020-12-31 00:00:00");
+    }
+
+    #[test]
+    fn unix_dates_in_the_future_belong_to_last_year() {
+        let item = parse_list_line("-rw-r--r-- 1 a b 10 Dec 24 09:00 old.log", today()).unwrap();
+        assert_eq!(item.modified, "2023-12-24 09:00:00");
+    }
+
+    #[test]
+    fn strips_symlink_target() {
+        let item = parse_list_line(
+            "lrwxrwxrwx 1 root root 7 Jan  1 00:00 www -> /var/www",
+            today(),
+        )
+        .unwrap();
+        assert_eq!(item.name, "www");
+        assert_eq!(item.kind, ListKind::Link);
+    }
+
+    #[test]
+    fn parses_dos_list_lines() {
+        let item =
+            parse_list_line("03-05-24  02:30PM       <DIR>          My Docs", today()).unwrap();
+        assert_eq!(item.name, "My Docs");
+        assert_eq!(item.kind, ListKind::Directory);
+        assert_eq!(item.modified, "2024-03-05 14:30:00");
+
+        let item = parse_list_line("12-01-2023  09:15AM             2048 a.bin", today()).unwrap();
+        assert_eq!(item.size, 2048);
+        assert_eq!(item.kind, ListKind::File);
+    }
+
+    #[test]
+    fn skips_summary_lines() {
+        assert!(parse_list_line("total 12", today()).is_none());
+    }
+
+    #[test]
+    fn port_command_uses_eprt_for_ipv6() {
+        let addr: SocketAddr = "[2001:db8::1]:50021".parse().unwrap();
+        assert_eq!(
+            port_command(addr),
+            ("EPRT", "|2|2001:db8::1|50021|".to_string())
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use crate::listing::Matcher;
+use crate::sftp_client::{shell_quote, ExecOutput};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_MAX_RESULTS: usize = 1000;
+// 내려받아 검색할 때 이보다 큰 파일은 건너뛴다
+pub const SCAN_SIZE_LIMIT: u64 = 10 * 1024 * 1024;
+// 서버에서 grep을 실행할 때 받을 출력의 최대 크기
+pub const EXEC_OUTPUT_LIMIT: usize = 16 * 1024 * 1024;
+// 서버의 grep이 이보다 오래 걸리면 멈추고 그때까지 찾은 결과만 돌려준다
+pub const EXEC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrepOptions {
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub include: Option<String>,
+    pub max_results: Option<usize>,
+    pub show_hidden: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrepMethod {
+    Exec,
+    Scan,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepReport {
+    pub matches: Vec<GrepMatch>,
+    pub method: GrepMethod,
+    pub truncated: bool,
+    pub skipped: Vec<String>,
+}
+
+impl GrepOptions {
+    pub fn max_results(&self) -> usize {
+        self.max_results
+            .filter(|m| *m > 0)
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+    }
+}
+
+pub fn build_command(path: &str, query: &str, options: &GrepOptions) -> String {
+    // -I: 바이너리 제외, --null: 파일명 뒤에 NUL을 붙여 ':'가 들어간 경로도 구분한다.
+    // BSD grep에서 -Z는 압축 해제 옵션이므로 GNU와 BSD 모두 같은 뜻인 --null을 쓴다.
+    let mut command = String::from("grep -rnI --null");
+    command.push_str(if options.regex { " -E" } else { " -F" });
+    if options.ignore_case {
+        command.push_str(" -i");
+    }
+    if let Some(include) = options.include.as_deref().filter(|i| !i.is_empty()) {
+        command.push_str(&format!(" --include={}", shell_quote(include)));
+    }
+    command.push_str(&format!(
+        " -e {} -- {}",
+        shell_quote(query),
+        shell_quote(path)
+    ));
+    command
+}
+
+/// grep 종료 코드 0은 일치, 1은 일치 없음, 2는 오류다. 권한 오류 등으로 2가 나와도
+/// 결과가 있으면 그대로 쓰고, 명령 자체를 쓸 수 없을 때만 None을 돌려준다.
+/// 출력 한도에 걸려 중간에 멈춘 경우에도 받은 만큼은 쓴다.
+pub fn parse_exec_output(output: &ExecOutput, max_results: usize) -> Option<GrepReport> {
+    let usable = match output.exit_status {
+        _ if output.truncated => !output.stdout.is_empty(),
+        0 => true,
+        1 => output.stderr.is_empty(),
+        2 => !output.stdout.is_empty(),
+        _ => false,
+    };
+    if !usable {
+        return None;
+    }
+
+    let mut matches = Vec::new();
+    let mut truncated = output.truncated;
+    let mut separated = false;
+
+    for line in output.stdout.split(|b| *b == b'\n') {
+        let Some(nul) = line.iter().position(|b| *b == 0) else {
+            continue;
+        };
+        separated = true;
+        let (path, rest) = (&line[..nul], &line[nul + 1..]);
+        let rest = String::from_utf8_lossy(rest);
+        let Some((number, text)) = rest.split_once(':') else {
+            continue;
+        };
+        let Ok(number) = number.parse() else {
+            continue;
+        };
+
+        if matches.len() >= max_results {
+            truncated = true;
+            break;
+        }
+
+        matches.push(GrepMatch {
+            path: String::from_utf8_lossy(path).to_string(),
+            line: number,
+            text: text.trim_end_matches('\r').to_string(),
+        });
+    }
+
+    // --null을 무시하는 grep이면 경로를 나눌 수 없으므로 내려받아 검색하게 한다
+    if !separated && !output.stdout.is_empty() {
+        return None;
+    }
+
+    Some(GrepReport {
+        matches,
+        method: GrepMethod::Exec,
+        truncated,
+        skipped: Vec::new(),
+    })
+}
+
+pub struct LineMatcher {
+    regex: regex::Regex,
+    include: Matcher,
+}
+
+impl LineMatcher {
+    pub fn new(query: &str, options: &GrepOptions) -> Result<Self, String> {
+        let pattern = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+
+        let regex = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(options.ignore_case)
+            .build()
+            .map_err(|e| format!("잘못된 정규식: {}", e))?;
+
+        Ok(Self {
+            regex,
+            include: Matcher::new(options.include.as_deref(), false)?,
+        })
+    }
+
+    pub fn includes(&self, name: &str) -> bool {
+        self.include.matches(name)
+    }
+
+    /// 파일 내용에서 일치하는 줄을 찾는다. NUL 바이트가 있으면 바이너리로 보고 건너뛴다.
+    pub fn scan(
+        &self,
+        path: &str,
+        data: &[u8],
+        limit: usize,
+        matches: &mut Vec<GrepMatch>,
+    ) -> bool {
+        if data.contains(&0) {
+            return true;
+        }
+
+        let text = String::from_utf8_lossy(data);
+        for (index, line) in text.lines().enumerate() {
+            if !self.regex.is_match(line) {
+                continue;
+            }
+            if matches.len() >= limit {
+                return false;
+            }
+            matches.push(GrepMatch {
+                path: path.to_string(),
+                line: index + 1,
+                text: line.to_string(),
+            });
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(stdout: &[u8], exit_status: i32, truncated: bool) -> ExecOutput {
+        ExecOutput {
+            stdout: stdout.to_vec(),
+            stderr: Vec::new(),
+            truncated,
+            exit_status,
+        }
+    }
+
+    #[test]
+    fn builds_portable_command() {
+        let options = GrepOptions {
+            ignore_case: true,
+            include: Some("*.rs".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_command("/srv/app", "it's", &options),
+            "grep -rnI --null -F -i --include='*.rs' -e 'it'\\''s' -- '/srv/app'"
+        );
+    }
+
+    #[test]
+    fn parses_nul_separated_paths() {
+        let report =
+            parse_exec_output(&output(b"/a:b.txt\x0012:hello: world\n", 0, false), 10).unwrap();
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(report.matches[0].path, "/a:b.txt");
+        assert_eq!(report.matches[0].line, 12);
+        assert_eq!(report.matches[0].text, "hello: world");
+        assert!(!report.truncated);
+    }
+
+    #[test]
+    fn falls_back_when_grep_ignores_null() {
+        assert!(parse_exec_output(&output(b"/a/b.txt:12:hello\n", 0, false), 10).is_none());
+    }
+
+    #[test]
+    fn keeps_partial_output_when_truncated() {
+        let report = parse_exec_output(&output(b"/a\x001:x\n/b\x002:y", -1, true), 10).unwrap();
+        assert!(report.truncated);
+        assert_eq!(report.matches.len(), 2);
+    }
+
+    #[test]
+    fn no_match_is_an_empty_report() {
+        let report = parse_exec_output(&output(b"", 1, false), 10).unwrap();
+        assert!(report.matches.is_empty());
+    }
+}
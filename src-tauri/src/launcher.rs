@@ -0,0 +1,416 @@
+use crate::EditorInfo;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const FILE_PLACEHOLDER: &str = "{file}";
+
+/// 따옴표로 묶인 인자를 하나로 취급해 명령줄을 나눈다
+fn split_command(command: &str) -> Vec<String> {
+    // Windows 경로의 구분자는 백슬래시이므로 이스케이프로 보지 않는다
+    split_command_with(command, cfg!(not(windows)))
+}
+
+fn split_command_with(command: &str, backslash_escapes: bool) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('"') | None) if backslash_escapes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                    in_arg = true;
+                }
+            }
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (c, _) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+fn spawn(program: &str, args: &[String]) -> Result<(), String> {
+    Command::new(program)
+        .args(args)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{} 실행 실패: {}", program, e))
+}
+
+/// "{file}" 자리에 파일 경로를 넣어 실행한다. 자리표시자가 없으면 마지막 인자로 붙인다.
+fn run_template(template: &str, file: &Path) -> Result<(), String> {
+    let file = file.to_string_lossy();
+    let mut args = split_command(template);
+    if args.is_empty() {
+        return Err("실행할 명령이 없습니다".to_string());
+    }
+
+    if template.contains(FILE_PLACEHOLDER) {
+        for arg in args.iter_mut() {
+            *arg = arg.replace(FILE_PLACEHOLDER, &file);
+        }
+    } else {
+        args.push(file.to_string());
+    }
+
+    let program = args.remove(0);
+    spawn(&program, &args)
+}
+
+struct DesktopEntry {
+    name: String,
+    exec: String,
+    no_display: bool,
+    is_editor: bool,
+}
+
+fn parse_desktop_entry(path: &Path) -> Option<DesktopEntry> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut in_entry = false;
+    let mut name = None;
+    let mut exec = None;
+    let mut kind = None;
+    let mut no_display = false;
+    let mut is_editor = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Name" => name = Some(value.to_string()),
+            "Exec" => exec = Some(value.to_string()),
+            "Type" => kind = Some(value.to_string()),
+            "NoDisplay" | "Hidden" if value == "true" => no_display = true,
+            "Categories" if value.split(';').any(|c| c == "TextEditor") => is_editor = true,
+            "MimeType" if value.split(';').any(|m| m == "text/plain") => is_editor = true,
+            _ => {}
+        }
+    }
+
+    if kind.as_deref() != Some("Application") {
+        return None;
+    }
+
+    Some(DesktopEntry {
+        name: name?,
+        exec: exec?,
+        no_display,
+        is_editor,
+    })
+}
+
+/// Exec 줄의 필드 코드(%f, %U 등)를 "{file}" 템플릿으로 바꾼다
+fn desktop_exec_template(exec: &str) -> String {
+    let mut template = String::new();
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            template.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('f' | 'F' | 'u' | 'U') => template.push_str(FILE_PLACEHOLDER),
+            Some('%') => template.push('%'),
+            _ => {}
+        }
+    }
+
+    if template.contains(FILE_PLACEHOLDER) {
+        template
+    } else {
+        format!("{} {}", template.trim_end(), FILE_PLACEHOLDER)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn desktop_dirs() -> Vec<std::path::PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(data_dir) = dirs::data_dir() {
+        dirs.push(data_dir.join("applications"));
+    }
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+        dirs.push(Path::new(dir).join("applications"));
+    }
+
+    dirs.push(std::path::PathBuf::from(
+        "/var/lib/flatpak/exports/share/applications",
+    ));
+    dirs
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn find_desktop_editors() -> Vec<EditorInfo> {
+    let mut editors: Vec<EditorInfo> = Vec::new();
+
+    for dir in desktop_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                continue;
+            }
+
+            // 같은 이름의 .desktop 파일은 앞선 디렉토리(사용자 설정)가 우선한다
+            let file_name = entry.file_name();
+            if editors
+                .iter()
+                .any(|e| Path::new(&e.path).file_name() == Some(&file_name))
+            {
+                continue;
+            }
+
+            match parse_desktop_entry(&path) {
+                Some(desktop) if desktop.is_editor && !desktop.no_display => {
+                    editors.push(EditorInfo {
+                        name: desktop.name,
+                        path: path.to_string_lossy().to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    editors.sort_by_key(|e| e.name.to_lowercase());
+    editors
+}
+
+// 터미널 안에서만 동작하는 에디터. 앱에서 띄우면 TTY가 없어 바로 종료된다
+const TERMINAL_EDITORS: &[&str] = &[
+    "vi", "vim", "nvim", "view", "nano", "pico", "micro", "hx", "helix", "kak", "joe", "jed", "ne",
+    "mg", "ed", "ex", "mcedit",
+];
+
+fn is_terminal_editor(command: &str) -> bool {
+    let args = split_command(command);
+    let Some(program) = args.first() else {
+        return false;
+    };
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+
+    TERMINAL_EDITORS.contains(&name)
+        || (name.starts_with("emacs")
+            && args
+                .iter()
+                .any(|a| matches!(a.as_str(), "-nw" | "-t" | "--tty")))
+}
+
+fn env_editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.trim().is_empty()))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_editors() -> Vec<EditorInfo> {
+    let editor_apps = [
+        ("Visual Studio Code", "/Applications/Visual Studio Code.app"),
+        ("Sublime Text", "/Applications/Sublime Text.app"),
+        ("TextMate", "/Applications/TextMate.app"),
+        ("BBEdit", "/Applications/BBEdit.app"),
+        ("Nova", "/Applications/Nova.app"),
+        ("Atom", "/Applications/Atom.app"),
+        ("CotEditor", "/Applications/CotEditor.app"),
+        ("TextEdit", "/Applications/TextEdit.app"),
+        ("Xcode", "/Applications/Xcode.app"),
+        ("IntelliJ IDEA", "/Applications/IntelliJ IDEA.app"),
+        ("IntelliJ IDEA CE", "/Applications/IntelliJ IDEA CE.app"),
+        ("PyCharm", "/Applications/PyCharm.app"),
+        ("WebStorm", "/Applications/WebStorm.app"),
+        ("Cursor", "/Applications/Cursor.app"),
+        ("Zed", "/Applications/Zed.app"),
+    ];
+
+    editor_apps
+        .iter()
+        .filter(|(_, path)| Path::new(path).exists())
+        .map(|(name, path)| EditorInfo {
+            name: name.to_string(),
+            path: path.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_editors() -> Vec<EditorInfo> {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+    let program_files = std::env::var("ProgramFiles").unwrap_or_default();
+
+    let candidates = [
+        (
+            "Visual Studio Code",
+            format!("{}\\Programs\\Microsoft VS Code\\Code.exe", local_app_data),
+        ),
+        (
+            "Notepad++",
+            format!("{}\\Notepad++\\notepad++.exe", program_files),
+        ),
+        (
+            "Sublime Text",
+            format!("{}\\Sublime Text\\sublime_text.exe", program_files),
+        ),
+        ("Notepad", "C:\\Windows\\notepad.exe".to_string()),
+    ];
+
+    candidates
+        .into_iter()
+        .filter(|(_, path)| Path::new(path).exists())
+        .map(|(name, path)| EditorInfo {
+            name: name.to_string(),
+            path,
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_editors() -> Vec<EditorInfo> {
+    find_desktop_editors()
+}
+
+pub fn available_editors() -> Vec<EditorInfo> {
+    let mut editors = platform_editors();
+
+    if let Some(editor) = env_editor().filter(|e| !is_terminal_editor(e)) {
+        editors.insert(
+            0,
+            EditorInfo {
+                name: format!("$VISUAL/$EDITOR ({})", editor),
+                path: editor,
+            },
+        );
+    }
+
+    editors
+}
+
+/// 설정된 에디터가 없으면 $VISUAL, $EDITOR 순으로 찾는다
+pub fn resolve_editor(configured: &str) -> Result<String, String> {
+    match configured.trim() {
+        "" => env_editor()
+            .ok_or("에디터가 설정되지 않았습니다. 설정에서 에디터를 지정해주세요.".to_string()),
+        editor => Ok(editor.to_string()),
+    }
+}
+
+/// 에디터로 파일을 연다. 에디터 값은 macOS 앱 번들, .desktop 파일,
+/// 실행 파일 경로, 또는 "{file}"이 들어간 명령 템플릿일 수 있다.
+pub fn open_with_editor(editor: &str, file: &Path) -> Result<(), String> {
+    let editor = editor.to_string();
+
+    // vim 같은 터미널 에디터는 열 수 없으므로 시스템 기본 앱으로 연다
+    if is_terminal_editor(&editor) {
+        return open_default(file);
+    }
+
+    if editor.contains(FILE_PLACEHOLDER) {
+        return run_template(&editor, file);
+    }
+
+    if editor.ends_with(".desktop") {
+        let desktop = parse_desktop_entry(Path::new(&editor))
+            .ok_or_else(|| format!("데스크톱 파일을 읽을 수 없습니다: {}", editor))?;
+        return run_template(&desktop_exec_template(&desktop.exec), file);
+    }
+
+    if cfg!(target_os = "macos") && editor.ends_with(".app") {
+        let args = vec!["-a".to_string(), editor, file.to_string_lossy().to_string()];
+        return spawn("open", &args);
+    }
+
+    // 공백이 든 경로라도 실제 파일이면 명령줄로 나누지 않는다
+    if Path::new(&editor).exists() {
+        return spawn(&editor, &[file.to_string_lossy().to_string()]);
+    }
+
+    run_template(&editor, file)
+}
+
+/// 시스템 기본 앱으로 연다. 파일 이름이 셸(cmd.exe 등)을 거치지 않도록 opener 플러그인을 쓴다.
+pub fn open_default(file: &Path) -> Result<(), String> {
+    tauri_plugin_opener::open_path(file, None::<&str>)
+        .map_err(|e| format!("기본 앱으로 열기 실패: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split_command(r#""/opt/My Editor/bin/edit" --wait '{file}'"#),
+            ["/opt/My Editor/bin/edit", "--wait", "{file}"]
+        );
+        assert_eq!(
+            split_command_with(r#"edit my\ file "a \"b\"""#, true),
+            ["edit", "my file", "a \"b\""]
+        );
+    }
+
+    #[test]
+    fn keeps_windows_backslashes() {
+        assert_eq!(
+            split_command_with(
+                r#""C:\Program Files\Notepad++\notepad++.exe" {file}"#,
+                false
+            ),
+            [r"C:\Program Files\Notepad++\notepad++.exe", "{file}"]
+        );
+    }
+
+    #[test]
+    fn detects_terminal_editors() {
+        assert!(is_terminal_editor("vim"));
+        assert!(is_terminal_editor("/usr/bin/nano -w"));
+        assert!(is_terminal_editor("emacs -nw"));
+        assert!(!is_terminal_editor("emacs"));
+        assert!(!is_terminal_editor("code --wait"));
+        assert!(!is_terminal_editor(""));
+    }
+}
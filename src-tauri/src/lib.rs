@@ -1,14 +1,28 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Mutex;
-use tauri::State;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
 
+mod archive;
+mod bookmark_file;
+mod bookmark_import;
+mod edit_session;
 mod ftp_client;
+mod grep;
+mod launcher;
+mod listing;
+mod preview;
+mod search;
 mod sftp_client;
 mod smb_client;
+mod ssh_config;
 mod storage;
+mod temp_files;
+mod terminal;
+mod thumbnail;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Connection {
     pub id: String,
@@ -19,6 +33,25 @@ pub struct Connection {
     pub username: String,
     pub password: String,
     pub share: Option<String>,
+    pub identity_file: Option<String>,
+    pub proxy_jump: Option<String>,
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    pub last_connected: Option<String>,
+    #[serde(default)]
+    pub connect_count: u32,
+    pub initial_path: Option<String>,
+    pub local_dir: Option<String>,
+    pub transfer_mode: Option<String>,
+    pub ftp_mode: Option<String>,
+    #[serde(default)]
+    pub ftp_nat_workaround: bool,
+    pub charset: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub last_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,54 +72,190 @@ pub enum ActiveConnection {
     None,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectInfo {
+    pub initial_path: String,
+    // initial_path를 열 수 없을 때 차례로 시도할 경로
+    pub fallback_paths: Vec<String>,
+    pub local_dir: Option<String>,
+}
+
+struct SessionInfo {
+    id: String,
+    bookmark: storage::BookmarkKey,
+    current_path: String,
+}
+
 pub struct AppState {
     connection: Mutex<ActiveConnection>,
+    session: Mutex<Option<SessionInfo>>,
+    listing: Mutex<listing::ListingCache>,
+    searches: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    execs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    shell_pump_running: AtomicBool,
+    edits: Mutex<HashMap<std::path::PathBuf, edit_session::EditedFile>>,
+    edit_watcher_running: AtomicBool,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             connection: Mutex::new(ActiveConnection::None),
+            session: Mutex::new(None),
+            listing: Mutex::new(listing::ListingCache::default()),
+            searches: Mutex::new(HashMap::new()),
+            execs: Mutex::new(HashMap::new()),
+            shell_pump_running: AtomicBool::new(false),
+            edits: Mutex::new(HashMap::new()),
+            edit_watcher_running: AtomicBool::new(false),
         }
     }
 }
 
+fn get_str(connection: &HashMap<String, serde_json::Value>, key: &str) -> Option<String> {
+    connection
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+fn end_session(state: &AppState) -> Result<(), String> {
+    let session = state.session.lock().map_err(|e| e.to_string())?.take();
+
+    if let Some(session) = session {
+        temp_files::remove_session(&session.id);
+        storage::set_last_path(&session.bookmark, &session.current_path)?;
+    }
+
+    Ok(())
+}
+
+/// 서버마다 같은 경로가 있을 수 있으므로 캐시 키에 접속 정보를 넣는다
+fn current_server_key(state: &AppState) -> Result<String, String> {
+    let session = state.session.lock().map_err(|e| e.to_string())?;
+    let bookmark = &session.as_ref().ok_or("Not connected")?.bookmark;
+
+    Ok(format!(
+        "{}://{}@{}:{}",
+        bookmark.protocol, bookmark.username, bookmark.host, bookmark.port
+    ))
+}
+
+fn current_session_id(state: &AppState) -> Result<String, String> {
+    state
+        .session
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|s| s.id.clone())
+        .ok_or_else(|| "Not connected".to_string())
+}
+
 #[tauri::command]
 async fn connect(
     connection: HashMap<String, serde_json::Value>,
+    app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<ConnectInfo, String> {
     let protocol = connection
         .get("protocol")
         .and_then(|v| v.as_str())
         .ok_or("Missing protocol")?;
-    let host = connection
+    let mut host = connection
         .get("host")
         .and_then(|v| v.as_str())
-        .ok_or("Missing host")?;
-    let port = connection
+        .ok_or("Missing host")?
+        .to_string();
+    let mut port = connection
         .get("port")
         .and_then(|v| v.as_u64())
         .ok_or("Missing port")? as u16;
-    let username = connection
+    let mut username = connection
         .get("username")
         .and_then(|v| v.as_str())
-        .ok_or("Missing username")?;
+        .ok_or("Missing username")?
+        .to_string();
     let password = connection
         .get("password")
         .and_then(|v| v.as_str())
         .ok_or("Missing password")?;
+    let mut identity_file = connection
+        .get("identityFile")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let mut proxy_jump = connection
+        .get("proxyJump")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
 
-    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+    if protocol == "sftp" {
+        if let Some(alias) = ssh_config::resolve(&host)? {
+            // 기본 포트 그대로일 때만 별칭의 Port를 쓴다. 직접 지정한 포트가 우선한다.
+            if let Some(alias_port) = alias.port.filter(|_| port == 0 || port == 22) {
+                port = alias_port;
+            }
+            if username.is_empty() {
+                username = alias.user.unwrap_or_default();
+            }
+            identity_file = identity_file.or(alias.identity_file);
+            proxy_jump = proxy_jump.or(alias.proxy_jump);
+            host = alias.host_name.unwrap_or(host);
+        }
+    }
+
+    let bookmark = storage::BookmarkKey {
+        id: get_str(&connection, "id"),
+        protocol: protocol.to_string(),
+        host: connection
+            .get("host")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        port: connection.get("port").and_then(|v| v.as_u64()).unwrap_or(0) as u16,
+        username: connection
+            .get("username")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+    };
+    let timeout_secs = match connection.get("timeoutSecs").and_then(|v| v.as_u64()) {
+        Some(secs) if secs > 0 => secs,
+        _ => storage::load_settings()?.connect_timeout_secs,
+    };
+    let timeout = std::time::Duration::from_secs(timeout_secs);
 
-    match protocol {
+    // 새 연결이 성공한 뒤에만 기존 세션을 정리한다. 실패하면 기존 연결을 그대로 쓸 수 있다.
+    let new_connection = match protocol {
         "ftp" => {
-            let ftp_conn = ftp_client::FtpConnection::connect(host, port, username, password)?;
-            *conn_guard = ActiveConnection::Ftp(ftp_conn);
+            let options = ftp_client::FtpOptions {
+                ascii: get_str(&connection, "transferMode").as_deref() == Some("ascii"),
+                mode: ftp_client::parse_mode(get_str(&connection, "ftpMode").as_deref()),
+                nat_workaround: connection
+                    .get("ftpNatWorkaround")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                timeout,
+                charset: get_str(&connection, "charset"),
+            };
+            let ftp_conn =
+                ftp_client::FtpConnection::connect(&host, port, &username, password, &options)?;
+            ActiveConnection::Ftp(ftp_conn)
         }
         "sftp" => {
-            let sftp_conn = sftp_client::SftpConnection::connect(host, port, username, password)?;
-            *conn_guard = ActiveConnection::Sftp(sftp_conn);
+            let sftp_conn = sftp_client::SftpConnection::connect(
+                &host,
+                port,
+                &username,
+                password,
+                identity_file.as_deref(),
+                proxy_jump.as_deref(),
+                timeout,
+            )?;
+            ActiveConnection::Sftp(sftp_conn)
         }
         "smb" => {
             let share = connection
@@ -95,34 +264,296 @@ async fn connect(
                 .filter(|s| !s.is_empty())
                 .ok_or("SMB 연결에는 공유폴더 이름이 필요합니다")?;
             let smb_conn =
-                smb_client::SmbConnection::connect(host, port, username, password, share)?;
-            *conn_guard = ActiveConnection::Smb(smb_conn);
+                smb_client::SmbConnection::connect(&host, port, &username, password, share)?;
+            ActiveConnection::Smb(smb_conn)
         }
         _ => return Err(format!("Unknown protocol: {}", protocol)),
+    };
+
+    // 편집 중인 파일을 기존 연결로 먼저 올린 뒤 세션 임시 폴더를 지운다
+    edit_session::close_session(&state, &app)?;
+    end_session(&state)?;
+    let saved = storage::find_connection(&bookmark).ok().flatten();
+
+    *state.connection.lock().map_err(|e| e.to_string())? = new_connection;
+
+    let _ = storage::record_connection_used(&bookmark);
+
+    let configured_path = get_str(&connection, "initialPath");
+    let initial_path = saved
+        .as_ref()
+        .and_then(|c| c.last_path.clone())
+        .or_else(|| configured_path.clone())
+        .unwrap_or_else(|| "/".to_string());
+    let mut fallback_paths = Vec::new();
+    for path in configured_path.into_iter().chain(["/".to_string()]) {
+        if path != initial_path && !fallback_paths.contains(&path) {
+            fallback_paths.push(path);
+        }
     }
+    let local_dir = get_str(&connection, "localDir");
 
-    Ok(())
+    state.listing.lock().map_err(|e| e.to_string())?.clear();
+    *state.session.lock().map_err(|e| e.to_string())? = Some(SessionInfo {
+        id: temp_files::new_session_id(),
+        bookmark,
+        current_path: initial_path.clone(),
+    });
+
+    Ok(ConnectInfo {
+        initial_path,
+        fallback_paths,
+        local_dir,
+    })
 }
 
 #[tauri::command]
-async fn disconnect(state: State<'_, AppState>) -> Result<(), String> {
+async fn disconnect(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    // 편집 중인 파일을 먼저 올린 뒤 세션 임시 폴더를 지운다
+    edit_session::close_session(&state, &app)?;
+    end_session(&state)?;
+    state.listing.lock().map_err(|e| e.to_string())?.clear();
+
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
     *conn_guard = ActiveConnection::None;
     Ok(())
 }
 
-#[tauri::command]
-async fn list_files(path: String, state: State<'_, AppState>) -> Result<Vec<FileEntry>, String> {
+fn resolve_show_hidden(show_hidden: Option<bool>) -> Result<bool, String> {
+    match show_hidden {
+        Some(show_hidden) => Ok(show_hidden),
+        None => Ok(storage::load_settings()?.show_hidden_files),
+    }
+}
+
+/// 연결에서 목록을 읽어 항목마다 on_entry를 부른다
+fn read_listing(
+    state: &AppState,
+    path: &str,
+    show_hidden: bool,
+    on_entry: &mut dyn FnMut(FileEntry),
+) -> Result<(), String> {
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
 
     match &mut *conn_guard {
-        ActiveConnection::Ftp(conn) => conn.list_files(&path),
-        ActiveConnection::Sftp(conn) => conn.list_files(&path),
-        ActiveConnection::Smb(conn) => conn.list_files(&path),
+        ActiveConnection::Ftp(conn) => conn.list_files_each(path, show_hidden, on_entry),
+        ActiveConnection::Sftp(conn) => conn.list_files_each(path, show_hidden, on_entry),
+        ActiveConnection::Smb(conn) => conn.list_files_each(path, show_hidden, on_entry),
         ActiveConnection::None => Err("Not connected".to_string()),
     }
 }
 
+fn set_current_path(state: &AppState, path: &str) -> Result<(), String> {
+    if let Some(session) = state.session.lock().map_err(|e| e.to_string())?.as_mut() {
+        session.current_path = path.to_string();
+    }
+    Ok(())
+}
+
+/// 캐시가 없거나 오래됐으면 서버에서 읽어 기본 순서로 정렬해 캐시에 넣는다.
+/// 서버에서 읽는 동안 항목마다 on_entry를 부르고, 정렬·필터가 적용된 목록을 f에 넘긴다.
+fn with_listing<T>(
+    state: &AppState,
+    path: &str,
+    show_hidden: bool,
+    max_age: std::time::Duration,
+    options: &listing::ListOptions,
+    on_entry: &mut dyn FnMut(&FileEntry),
+    f: impl FnOnce(&[FileEntry]) -> T,
+) -> Result<T, String> {
+    {
+        let mut cache = state.listing.lock().map_err(|e| e.to_string())?;
+        if let Some(view) = cache.view(path, show_hidden, max_age, options) {
+            let result = f(view?);
+            drop(cache);
+            set_current_path(state, path)?;
+            return Ok(result);
+        }
+    }
+
+    let mut entries = Vec::new();
+    read_listing(state, path, show_hidden, &mut |entry| {
+        on_entry(&entry);
+        entries.push(entry);
+    })?;
+    let entries = listing::apply(&entries, &listing::ListOptions::default())?;
+
+    let mut cache = state.listing.lock().map_err(|e| e.to_string())?;
+    cache.insert(path, show_hidden, entries);
+    let view = cache
+        .view(path, show_hidden, std::time::Duration::MAX, options)
+        .ok_or("목록을 캐시에서 찾을 수 없습니다")??;
+    let result = f(view);
+    drop(cache);
+
+    set_current_path(state, path)?;
+    Ok(result)
+}
+
+fn fetch_listing(
+    state: &AppState,
+    path: &str,
+    show_hidden: bool,
+    max_age: std::time::Duration,
+) -> Result<Vec<FileEntry>, String> {
+    with_listing(
+        state,
+        path,
+        show_hidden,
+        max_age,
+        &listing::ListOptions::default(),
+        &mut |_| {},
+        |entries| entries.to_vec(),
+    )
+}
+
+fn listing_max_age(refresh: Option<bool>) -> Result<std::time::Duration, String> {
+    if refresh.unwrap_or(false) {
+        return Ok(std::time::Duration::ZERO);
+    }
+
+    let ttl = storage::load_settings()?.listing_cache_ttl_secs;
+    Ok(std::time::Duration::from_secs(ttl))
+}
+
+fn invalidate_listing(state: &AppState, path: &str) -> Result<(), String> {
+    state
+        .listing
+        .lock()
+        .map_err(|e| e.to_string())?
+        .invalidate(path);
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_files(
+    path: String,
+    show_hidden: Option<bool>,
+    refresh: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FileEntry>, String> {
+    let show_hidden = resolve_show_hidden(show_hidden)?;
+    fetch_listing(&state, &path, show_hidden, listing_max_age(refresh)?)
+}
+
+#[tauri::command]
+async fn list_files_page(
+    path: String,
+    show_hidden: Option<bool>,
+    options: listing::ListOptions,
+    refresh: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<listing::ListPage, String> {
+    let show_hidden = resolve_show_hidden(show_hidden)?;
+
+    // 이어지는 페이지는 TTL이 지났더라도 첫 페이지와 같은 목록에서 잘라낸다
+    let max_age = if options.offset > 0 {
+        std::time::Duration::MAX
+    } else {
+        listing_max_age(refresh)?
+    };
+
+    with_listing(
+        &state,
+        &path,
+        show_hidden,
+        max_age,
+        &options,
+        &mut |_| {},
+        |entries| listing::page(entries, options.offset, options.limit),
+    )
+}
+
+// 서버에서 읽는 중인 항목을 모아 보내는 간격
+const STREAM_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+#[tauri::command]
+async fn list_files_stream(
+    path: String,
+    show_hidden: Option<bool>,
+    options: listing::ListOptions,
+    request_id: String,
+    refresh: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let show_hidden = resolve_show_hidden(show_hidden)?;
+    let matcher = listing::Matcher::new(options.filter.as_deref(), options.regex)?;
+    let chunk_size = options
+        .limit
+        .filter(|l| *l > 0)
+        .unwrap_or(listing::CHUNK_SIZE);
+
+    let emit = |entries: Vec<FileEntry>, offset: usize, total: usize, sorted: bool, done: bool| {
+        app.emit(
+            "list-files-chunk",
+            listing::ListChunk {
+                request_id: request_id.clone(),
+                path: path.clone(),
+                entries,
+                offset,
+                total,
+                sorted,
+                done,
+            },
+        )
+        .map_err(|e| format!("목록 전송 실패: {}", e))
+    };
+
+    // 서버에서 읽는 동안에는 받은 순서대로 보내 첫 화면을 빨리 채운다
+    let mut pending = Vec::new();
+    let mut streamed = 0;
+    let mut last_flush = std::time::Instant::now();
+    let mut stream_error = None;
+    let mut on_entry = |entry: &FileEntry| {
+        if !matcher.matches(&entry.name) {
+            return;
+        }
+        pending.push(entry.clone());
+
+        if pending.len() >= chunk_size || last_flush.elapsed() >= STREAM_FLUSH_INTERVAL {
+            let chunk = std::mem::take(&mut pending);
+            let offset = streamed;
+            streamed += chunk.len();
+            if let Err(e) = emit(chunk, offset, streamed, false, false) {
+                stream_error.get_or_insert(e);
+            }
+            last_flush = std::time::Instant::now();
+        }
+    };
+
+    let sorted = with_listing(
+        &state,
+        &path,
+        show_hidden,
+        listing_max_age(refresh)?,
+        &options,
+        &mut on_entry,
+        |entries| entries.to_vec(),
+    )?;
+    if let Some(e) = stream_error {
+        return Err(e);
+    }
+
+    // 정렬된 전체 목록을 처음부터 다시 보낸다
+    let total = sorted.len();
+    let mut offset = 0;
+
+    loop {
+        let chunk: Vec<FileEntry> = sorted[offset..(offset + chunk_size).min(total)].to_vec();
+        let next = offset + chunk.len();
+
+        emit(chunk, offset, total, true, next >= total)?;
+
+        if next >= total {
+            break;
+        }
+        offset = next;
+    }
+
+    Ok(total)
+}
+
 #[tauri::command]
 async fn download_file(
     remote_path: String,
@@ -148,9 +579,34 @@ async fn upload_file(
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
 
     match &mut *conn_guard {
-        ActiveConnection::Ftp(conn) => conn.upload(&local_path, &remote_path),
-        ActiveConnection::Sftp(conn) => conn.upload(&local_path, &remote_path),
-        ActiveConnection::Smb(conn) => conn.upload(&local_path, &remote_path),
+        ActiveConnection::Ftp(conn) => conn.upload(&local_path, &remote_path)?,
+        ActiveConnection::Sftp(conn) => conn.upload(&local_path, &remote_path)?,
+        ActiveConnection::Smb(conn) => conn.upload(&local_path, &remote_path)?,
+        ActiveConnection::None => return Err("Not connected".to_string()),
+    }
+
+    invalidate_listing(&state, &remote_path)
+}
+
+fn stat_remote(conn: &mut ActiveConnection, remote_path: &str) -> Result<FileEntry, String> {
+    match conn {
+        ActiveConnection::Ftp(ftp) => ftp.stat(remote_path),
+        ActiveConnection::Sftp(sftp) => sftp.stat(remote_path),
+        ActiveConnection::Smb(smb) => smb.stat(remote_path),
+        ActiveConnection::None => Err("Not connected".to_string()),
+    }
+}
+
+fn read_remote_range(
+    conn: &mut ActiveConnection,
+    remote_path: &str,
+    offset: u64,
+    length: u64,
+) -> Result<Vec<u8>, String> {
+    match conn {
+        ActiveConnection::Ftp(ftp) => ftp.read_range(remote_path, offset, length),
+        ActiveConnection::Sftp(sftp) => sftp.read_range(remote_path, offset, length),
+        ActiveConnection::Smb(smb) => smb.read_range(remote_path, offset, length),
         ActiveConnection::None => Err("Not connected".to_string()),
     }
 }
@@ -161,33 +617,327 @@ async fn preview_file_base64(
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
-    
-    let data = match &mut *conn_guard {
-        ActiveConnection::Ftp(conn) => conn.read_file(&remote_path)?,
-        ActiveConnection::Sftp(conn) => conn.read_file(&remote_path)?,
-        ActiveConnection::Smb(conn) => conn.read_file(&remote_path)?,
-        ActiveConnection::None => return Err("Not connected".to_string()),
-    };
-    
+
+    let size = stat_remote(&mut conn_guard, &remote_path)?.size;
+    if size > preview::BINARY_PREVIEW_LIMIT {
+        return Err(format!(
+            "파일이 너무 커서 미리볼 수 없습니다 ({}MB 초과)",
+            preview::BINARY_PREVIEW_LIMIT / 1024 / 1024
+        ));
+    }
+
+    // FTP에서 SIZE를 지원하지 않으면 크기가 0으로 오므로 상한까지만 읽는다
+    let data = read_remote_range(&mut conn_guard, &remote_path, 0, preview::BINARY_PREVIEW_LIMIT)?;
+
     use base64::{Engine as _, engine::general_purpose::STANDARD};
     Ok(STANDARD.encode(&data))
 }
 
 #[tauri::command]
-async fn preview_file_text(
+async fn preview_thumbnail(
     remote_path: String,
+    max_size: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
+) -> Result<thumbnail::Thumbnail, String> {
+    let max_size = thumbnail::clamp_size(max_size);
+    let server = current_server_key(&state)?;
+
+    let (data, key) = {
+        let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+
+        let entry = stat_remote(&mut conn_guard, &remote_path)?;
+        // 수정 시각을 알 수 없으면 바뀐 파일을 구분할 수 없으므로 캐시하지 않는다
+        let key = (!entry.modified.is_empty()).then(|| {
+            thumbnail::cache_key(&server, &remote_path, &entry.modified, entry.size, max_size)
+        });
+        if let Some(cached) = key.as_deref().and_then(thumbnail::load_cached) {
+            return Ok(cached);
+        }
+        if entry.size > preview::BINARY_PREVIEW_LIMIT {
+            return Err(format!(
+                "파일이 너무 커서 미리볼 수 없습니다 ({}MB 초과)",
+                preview::BINARY_PREVIEW_LIMIT / 1024 / 1024
+            ));
+        }
+
+        let data = read_remote_range(&mut conn_guard, &remote_path, 0, preview::BINARY_PREVIEW_LIMIT)?;
+        (data, key)
+    };
+
+    // 디코딩은 오래 걸릴 수 있으므로 연결을 놓은 뒤에 한다
+    thumbnail::generate(&data, max_size, key.as_deref())
+}
+
+/// 아카이브를 세션 임시 폴더에 내려받는다. 편집 중인 같은 파일과 겹치지 않도록 별도 위치를 쓴다.
+fn download_archive(
+    state: &AppState,
+    remote_path: &str,
+    max_size: Option<u64>,
+) -> Result<std::path::PathBuf, String> {
+    let file_name = remote_path.rsplit('/').next().unwrap_or("archive");
+    let temp_file = temp_files::path_for(
+        &current_session_id(state)?,
+        &format!("archive:{}", remote_path),
+        file_name,
+    )?;
+    let local_path = temp_file.to_string_lossy().to_string();
+
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
-    
-    let data = match &mut *conn_guard {
-        ActiveConnection::Ftp(conn) => conn.read_file(&remote_path)?,
-        ActiveConnection::Sftp(conn) => conn.read_file(&remote_path)?,
-        ActiveConnection::Smb(conn) => conn.read_file(&remote_path)?,
-        ActiveConnection::None => return Err("Not connected".to_string()),
+
+    if let Some(limit) = max_size {
+        if stat_remote(&mut conn_guard, remote_path)?.size > limit {
+            return Err(format!(
+                "파일이 너무 커서 미리볼 수 없습니다 ({}MB 초과)",
+                limit / 1024 / 1024
+            ));
+        }
+    }
+
+    let result = match &mut *conn_guard {
+        ActiveConnection::None => Err("연결되어 있지 않습니다".to_string()),
+        ActiveConnection::Ftp(ftp) => ftp.download(remote_path, &local_path),
+        ActiveConnection::Sftp(sftp) => sftp.download(remote_path, &local_path),
+        ActiveConnection::Smb(smb) => smb.download(remote_path, &local_path),
     };
-    
-    String::from_utf8(data).map_err(|_| "파일을 텍스트로 읽을 수 없습니다".to_string())
+
+    // 받다 만 파일을 남기지 않는다
+    if let Err(e) = result {
+        std::fs::remove_file(&temp_file).ok();
+        return Err(e);
+    }
+
+    Ok(temp_file)
+}
+
+#[tauri::command]
+async fn preview_archive(
+    remote_path: String,
+    state: State<'_, AppState>,
+) -> Result<archive::ArchiveListing, String> {
+    let format = archive::ArchiveFormat::from_name(&remote_path)
+        .ok_or_else(|| "지원하지 않는 압축 형식입니다".to_string())?;
+
+    // ZIP은 끝의 중앙 디렉터리만 읽으면 목록을 알 수 있다
+    if format == archive::ArchiveFormat::Zip {
+        let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+        let size = stat_remote(&mut conn_guard, &remote_path)?.size;
+
+        // 크기를 모르면(SIZE를 지원하지 않는 FTP) 끝을 찾을 수 없으므로 내려받아서 읽는다
+        if size > 0 {
+            return archive::list_zip_ranges(
+                size,
+                archive::MAX_PREVIEW_ENTRIES,
+                &mut |offset, length| {
+                    read_remote_range(&mut conn_guard, &remote_path, offset, length)
+                },
+            );
+        }
+    }
+
+    let temp_file = download_archive(&state, &remote_path, Some(archive::ARCHIVE_PREVIEW_LIMIT))?;
+    let listing = archive::list_entries(&temp_file, format, archive::MAX_PREVIEW_ENTRIES);
+    std::fs::remove_file(&temp_file).ok();
+
+    listing
+}
+
+fn create_remote_dir(conn: &mut ActiveConnection, remote_path: &str) -> Result<(), String> {
+    match conn {
+        ActiveConnection::Ftp(ftp) => ftp.create_dir(remote_path),
+        ActiveConnection::Sftp(sftp) => sftp.create_dir(remote_path),
+        ActiveConnection::Smb(smb) => smb.create_dir(remote_path),
+        ActiveConnection::None => Err("Not connected".to_string()),
+    }
+}
+
+/// 원격 폴더에 있는 이름들. FTP의 stat은 없는 파일에도 성공하므로 존재 여부는 상위 폴더 목록으로 확인한다.
+/// 폴더마다 한 번만 읽고, 읽을 수 없는 폴더(아직 없는 폴더)는 비어 있는 것으로 본다.
+fn remote_names<'a>(
+    conn: &mut ActiveConnection,
+    cache: &'a mut HashMap<String, HashSet<String>>,
+    dir: &str,
+) -> &'a mut HashSet<String> {
+    cache.entry(dir.to_string()).or_insert_with(|| {
+        let entries = match conn {
+            ActiveConnection::Ftp(ftp) => ftp.list_files(dir, true),
+            ActiveConnection::Sftp(sftp) => sftp.list_files(dir, true),
+            ActiveConnection::Smb(smb) => smb.list_files(dir, true),
+            ActiveConnection::None => Ok(Vec::new()),
+        };
+        entries
+            .map(|entries| entries.into_iter().map(|e| e.name).collect())
+            .unwrap_or_default()
+    })
+}
+
+/// 로컬에 풀어 둔 트리를 원격 폴더로 올린다
+fn upload_extracted(
+    state: &AppState,
+    staging: &std::path::Path,
+    destination: &str,
+    policy: storage::OverwritePolicy,
+    rejected: usize,
+) -> Result<archive::ExtractSummary, String> {
+    let root = destination.trim_end_matches('/');
+    let mut entries = Vec::new();
+
+    for entry in walkdir::WalkDir::new(staging).min_depth(1) {
+        let entry = entry.map_err(|e| format!("디렉토리 탐색 실패: {}", e))?;
+        let relative = entry
+            .path()
+            .strip_prefix(staging)
+            .map_err(|e| format!("경로 처리 실패: {}", e))?;
+        let relative: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        entries.push((
+            format!("{}/{}", root, relative.join("/")),
+            entry.path().to_path_buf(),
+            entry.file_type().is_dir(),
+        ));
+    }
+
+    let mut summary = archive::ExtractSummary {
+        rejected,
+        ..Default::default()
+    };
+    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+    let mut names = HashMap::new();
+
+    if policy == storage::OverwritePolicy::Ask {
+        for (remote, _, is_dir) in &entries {
+            let (dir, name) = remote.rsplit_once('/').unwrap_or(("", remote));
+            if !is_dir && remote_names(&mut conn_guard, &mut names, dir).contains(name) {
+                return Err(format!("이미 존재하는 파일: {}", remote));
+            }
+        }
+    }
+
+    // 이미 있는 폴더면 생성이 실패하므로 오류는 무시한다. 정말 만들 수 없으면 업로드에서 드러난다.
+    let _ = create_remote_dir(&mut conn_guard, root);
+
+    for (remote, local, is_dir) in entries {
+        if is_dir {
+            let _ = create_remote_dir(&mut conn_guard, &remote);
+            continue;
+        }
+
+        let (dir, name) = remote.rsplit_once('/').unwrap_or(("", &remote));
+        let existing = remote_names(&mut conn_guard, &mut names, dir);
+        let remote = if !existing.contains(name) {
+            remote.clone()
+        } else {
+            match policy {
+                storage::OverwritePolicy::Skip => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                storage::OverwritePolicy::Rename => {
+                    let renamed = (1..)
+                        .map(|n| archive::numbered_name(name, n))
+                        .find(|candidate| !existing.contains(candidate))
+                        .unwrap_or_else(|| name.to_string());
+                    format!("{}/{}", dir, renamed)
+                }
+                storage::OverwritePolicy::Overwrite | storage::OverwritePolicy::Ask => {
+                    remote.clone()
+                }
+            }
+        };
+        if let Some((_, name)) = remote.rsplit_once('/') {
+            existing.insert(name.to_string());
+        }
+
+        let local = local.to_string_lossy().to_string();
+        match &mut *conn_guard {
+            ActiveConnection::Ftp(conn) => conn.upload(&local, &remote)?,
+            ActiveConnection::Sftp(conn) => conn.upload(&local, &remote)?,
+            ActiveConnection::Smb(conn) => conn.upload(&local, &remote)?,
+            ActiveConnection::None => return Err("Not connected".to_string()),
+        }
+        summary.extracted += 1;
+    }
+
+    drop(conn_guard);
+    invalidate_listing(state, root)?;
+
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn extract_archive(
+    remote_path: String,
+    destination: String,
+    target: archive::ExtractTarget,
+    overwrite: Option<storage::OverwritePolicy>,
+    state: State<'_, AppState>,
+) -> Result<archive::ExtractSummary, String> {
+    let format = archive::ArchiveFormat::from_name(&remote_path)
+        .ok_or_else(|| "지원하지 않는 압축 형식입니다".to_string())?;
+    // 지정하지 않으면 설정의 덮어쓰기 정책을 따른다
+    let policy = match overwrite {
+        Some(policy) => policy,
+        None => storage::load_settings()?.overwrite_policy,
+    };
+
+    let temp_file = download_archive(&state, &remote_path, None)?;
+
+    let result = match target {
+        archive::ExtractTarget::Local => {
+            archive::extract(&temp_file, format, std::path::Path::new(&destination), policy)
+        }
+        archive::ExtractTarget::Remote => {
+            // 원격으로 풀 때는 세션 임시 폴더에 먼저 풀고 올린다
+            let staging = temp_files::dir_for(
+                &current_session_id(&state)?,
+                &format!("extract:{}", uuid_simple()),
+            )?;
+            let result = archive::extract(&temp_file, format, &staging, storage::OverwritePolicy::Overwrite)
+                .and_then(|extracted| {
+                    upload_extracted(&state, &staging, &destination, policy, extracted.rejected)
+                });
+            std::fs::remove_dir_all(&staging).ok();
+            result
+        }
+    };
+
+    std::fs::remove_file(&temp_file).ok();
+    result
+}
+
+#[tauri::command]
+async fn preview_file_text(
+    remote_path: String,
+    max_bytes: Option<u64>,
+    tail: Option<bool>,
+    charset: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<preview::TextPreview, String> {
+    let max_bytes = max_bytes
+        .filter(|m| *m > 0)
+        .unwrap_or(preview::TEXT_PREVIEW_BYTES);
+    let tail = tail.unwrap_or(false);
+
+    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+
+    let total_size = stat_remote(&mut conn_guard, &remote_path)?.size;
+    let (offset, length) = preview::range_for(total_size, max_bytes, tail);
+    let data = read_remote_range(&mut conn_guard, &remote_path, offset, length)?;
+
+    let cut_end = !tail && data.len() as u64 == max_bytes && total_size != max_bytes;
+    let decoded = preview::decode_text(&data, offset, cut_end, charset.as_deref())?;
+
+    Ok(preview::TextPreview {
+        line_ending: preview::detect_line_ending(&decoded.text),
+        encoding: decoded.encoding.name().to_string(),
+        had_errors: decoded.had_errors,
+        text: decoded.text,
+        offset,
+        total_size: total_size.max(offset + data.len() as u64),
+        truncated: offset > 0 || cut_end,
+    })
 }
 
 #[tauri::command]
@@ -199,23 +949,49 @@ async fn delete_file(
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
 
     match &mut *conn_guard {
-        ActiveConnection::Ftp(conn) => conn.delete(&remote_path, is_directory),
-        ActiveConnection::Sftp(conn) => conn.delete(&remote_path, is_directory),
-        ActiveConnection::Smb(conn) => conn.delete(&remote_path, is_directory),
-        ActiveConnection::None => Err("Not connected".to_string()),
+        ActiveConnection::Ftp(conn) => conn.delete(&remote_path, is_directory)?,
+        ActiveConnection::Sftp(conn) => conn.delete(&remote_path, is_directory)?,
+        ActiveConnection::Smb(conn) => conn.delete(&remote_path, is_directory)?,
+        ActiveConnection::None => return Err("Not connected".to_string()),
     }
+
+    invalidate_listing(&state, &remote_path)
+}
+
+#[tauri::command]
+async fn rename_file(
+    from_path: String,
+    to_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+
+    match &mut *conn_guard {
+        ActiveConnection::Ftp(conn) => conn.rename(&from_path, &to_path)?,
+        ActiveConnection::Sftp(conn) => conn.rename(&from_path, &to_path)?,
+        ActiveConnection::Smb(conn) => conn.rename(&from_path, &to_path)?,
+        ActiveConnection::None => return Err("Not connected".to_string()),
+    }
+
+    invalidate_listing(&state, &from_path)?;
+    invalidate_listing(&state, &to_path)
+}
+
+#[tauri::command]
+async fn clear_listing_cache(state: State<'_, AppState>) -> Result<(), String> {
+    state.listing.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
 }
 
 #[tauri::command]
 async fn open_with_editor(
     remote_path: String,
     file_name: String,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let editor_path = storage::get_editor_path()?;
-    if editor_path.is_empty() {
-        return Err("에디터가 설정되지 않았습니다. 설정에서 에디터를 지정해주세요.".to_string());
-    }
+    let editor = launcher::resolve_editor(&storage::get_editor_path()?)?;
+    let temp_file = temp_files::path_for(&current_session_id(&state)?, &remote_path, &file_name)?;
 
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
     
@@ -226,19 +1002,59 @@ async fn open_with_editor(
         ActiveConnection::None => return Err("Not connected".to_string()),
     };
 
-    let temp_dir = std::env::temp_dir().join("easyftp");
-    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("임시 폴더 생성 실패: {}", e))?;
-    
-    let temp_file = temp_dir.join(&file_name);
     std::fs::write(&temp_file, &data).map_err(|e| format!("임시 파일 저장 실패: {}", e))?;
 
-    std::process::Command::new("open")
-        .arg("-a")
-        .arg(&editor_path)
-        .arg(&temp_file)
-        .spawn()
-        .map_err(|e| format!("에디터 실행 실패: {}", e))?;
+    let edit = edit_session::EditedFile::new(&mut conn_guard, &remote_path, temp_file.clone())?;
+    {
+        let mut edits = state.edits.lock().map_err(|e| e.to_string())?;
+        edits.insert(temp_file.clone(), edit);
+
+        if !state.edit_watcher_running.swap(true, Ordering::SeqCst) {
+            edit_session::spawn_watcher(app);
+        }
+    }
+
+    launcher::open_with_editor(&editor, &temp_file)
+}
+
+#[tauri::command]
+async fn get_edited_files(
+    state: State<'_, AppState>,
+) -> Result<Vec<edit_session::EditedFileInfo>, String> {
+    let edits = state.edits.lock().map_err(|e| e.to_string())?;
+    Ok(edits.values().map(|e| e.info()).collect())
+}
+
+#[tauri::command]
+async fn upload_edited_file(
+    local_path: String,
+    force: bool,
+    state: State<'_, AppState>,
+) -> Result<edit_session::EditSyncEvent, String> {
+    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+    let mut edits = state.edits.lock().map_err(|e| e.to_string())?;
 
+    let edit = edits
+        .get_mut(std::path::Path::new(&local_path))
+        .ok_or("편집 중인 파일이 아닙니다")?;
+    let event = edit.sync(&mut conn_guard, force);
+    drop(conn_guard);
+
+    invalidate_listing(&state, &edit.remote_path)?;
+    Ok(event)
+}
+
+#[tauri::command]
+async fn stop_editing(local_path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let edit = state
+        .edits
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(std::path::Path::new(&local_path));
+
+    if let Some(edit) = edit {
+        edit.remove_temp_file();
+    }
     Ok(())
 }
 
@@ -248,6 +1064,8 @@ async fn open_with_default_app(
     file_name: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    let temp_file = temp_files::path_for(&current_session_id(&state)?, &remote_path, &file_name)?;
+
     let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
     
     let data = match &mut *conn_guard {
@@ -257,20 +1075,178 @@ async fn open_with_default_app(
         ActiveConnection::None => return Err("Not connected".to_string()),
     };
 
-    let temp_dir = std::env::temp_dir().join("easyftp");
-    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("임시 폴더 생성 실패: {}", e))?;
-    
-    let temp_file = temp_dir.join(&file_name);
     std::fs::write(&temp_file, &data).map_err(|e| format!("임시 파일 저장 실패: {}", e))?;
 
-    std::process::Command::new("open")
-        .arg(&temp_file)
-        .spawn()
-        .map_err(|e| format!("파일 열기 실패: {}", e))?;
+    launcher::open_default(&temp_file)
+}
+
+// 실행 중인 명령에 읽을 출력이 없을 때 다시 확인하기까지의 간격
+const EXEC_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// SFTP 연결에서 명령을 실행한다. 연결은 출력을 읽을 때만 잠깐씩 잡으므로 오래 걸리는 명령이
+/// 목록 조회나 전송을 막지 않는다. stopped가 true를 돌려주면 채널을 닫고 None을 돌려준다.
+fn run_remote_exec(
+    state: &AppState,
+    command: &str,
+    stopped: &dyn Fn() -> bool,
+    on_output: &mut dyn FnMut(sftp_client::ExecStream, &[u8]),
+) -> Result<Option<i32>, String> {
+    let session_id = current_session_id(state)?;
+    let mut channel = match &mut *state.connection.lock().map_err(|e| e.to_string())? {
+        ActiveConnection::Sftp(sftp) => sftp.start_exec(command)?,
+        ActiveConnection::None => return Err("Not connected".to_string()),
+        _ => return Err("원격 명령 실행은 SFTP 연결에서만 지원합니다".to_string()),
+    };
+
+    loop {
+        let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+        // 그 사이에 다른 서버로 다시 연결했다면 채널이 이전 세션의 것이다
+        let sftp = match &mut *conn_guard {
+            ActiveConnection::Sftp(sftp) if current_session_id(state)? == session_id => sftp,
+            _ => return Err("명령 실행 중 연결이 끊어졌습니다".to_string()),
+        };
+
+        if stopped() {
+            sftp.close_exec(&mut channel);
+            return Ok(None);
+        }
+
+        match sftp.poll_exec(&mut channel, on_output)? {
+            sftp_client::ExecPoll::Finished(status) => return Ok(Some(status)),
+            sftp_client::ExecPoll::Output => {}
+            sftp_client::ExecPoll::Idle => {
+                drop(conn_guard);
+                std::thread::sleep(EXEC_POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+async fn exec_command(
+    command: String,
+    exec_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<terminal::ExecResult, String> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    state
+        .execs
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(exec_id.clone(), cancelled.clone());
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+
+    let result = run_remote_exec(
+        &state,
+        &command,
+        &|| cancelled.load(Ordering::Relaxed),
+        &mut |stream, data| {
+            let (name, pending) = match stream {
+                sftp_client::ExecStream::Stdout => ("stdout", &mut stdout),
+                sftp_client::ExecStream::Stderr => ("stderr", &mut stderr),
+            };
+            let data = terminal::decode_utf8(pending, data);
+            if !data.is_empty() {
+                let _ = app.emit(
+                    "exec-output",
+                    terminal::ExecOutputEvent {
+                        exec_id: exec_id.clone(),
+                        stream: name,
+                        data,
+                    },
+                );
+            }
+        },
+    );
+
+    state
+        .execs
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&exec_id);
+
+    let exit_status = result?;
+    Ok(terminal::ExecResult {
+        exit_status,
+        cancelled: exit_status.is_none(),
+    })
+}
 
+#[tauri::command]
+async fn cancel_exec(exec_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(flag) = state
+        .execs
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&exec_id)
+    {
+        flag.store(true, Ordering::Relaxed);
+    }
     Ok(())
 }
 
+fn with_sftp<T>(
+    state: &AppState,
+    f: impl FnOnce(&mut sftp_client::SftpConnection) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
+
+    match &mut *conn_guard {
+        ActiveConnection::Sftp(sftp) => f(sftp),
+        ActiveConnection::None => Err("Not connected".to_string()),
+        _ => Err("터미널은 SFTP 연결에서만 지원합니다".to_string()),
+    }
+}
+
+#[tauri::command]
+async fn open_shell(
+    cols: u32,
+    rows: u32,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let shell_id = uuid_simple();
+
+    with_sftp(&state, |sftp| {
+        sftp.open_shell(&shell_id, cols, rows)?;
+
+        // 출력 수집 스레드는 연결을 잡은 상태에서만 켜고 끈다
+        if !state.shell_pump_running.swap(true, Ordering::SeqCst) {
+            terminal::spawn_shell_pump(app);
+        }
+        Ok(())
+    })?;
+
+    Ok(shell_id)
+}
+
+#[tauri::command]
+async fn write_shell(
+    shell_id: String,
+    data: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    with_sftp(&state, |sftp| sftp.write_shell(&shell_id, data.as_bytes()))
+}
+
+#[tauri::command]
+async fn resize_shell(
+    shell_id: String,
+    cols: u32,
+    rows: u32,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    with_sftp(&state, |sftp| sftp.resize_shell(&shell_id, cols, rows))
+}
+
+#[tauri::command]
+async fn close_shell(shell_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    with_sftp(&state, |sftp| sftp.close_shell(&shell_id))
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EditorInfo {
@@ -280,36 +1256,7 @@ pub struct EditorInfo {
 
 #[tauri::command]
 async fn get_available_editors() -> Result<Vec<EditorInfo>, String> {
-    let mut editors = Vec::new();
-    
-    let editor_apps = [
-        ("Visual Studio Code", "/Applications/Visual Studio Code.app"),
-        ("Sublime Text", "/Applications/Sublime Text.app"),
-        ("TextMate", "/Applications/TextMate.app"),
-        ("BBEdit", "/Applications/BBEdit.app"),
-        ("Nova", "/Applications/Nova.app"),
-        ("Atom", "/Applications/Atom.app"),
-        ("CotEditor", "/Applications/CotEditor.app"),
-        ("TextEdit", "/Applications/TextEdit.app"),
-        ("Xcode", "/Applications/Xcode.app"),
-        ("IntelliJ IDEA", "/Applications/IntelliJ IDEA.app"),
-        ("IntelliJ IDEA CE", "/Applications/IntelliJ IDEA CE.app"),
-        ("PyCharm", "/Applications/PyCharm.app"),
-        ("WebStorm", "/Applications/WebStorm.app"),
-        ("Cursor", "/Applications/Cursor.app"),
-        ("Zed", "/Applications/Zed.app"),
-    ];
-
-    for (name, path) in editor_apps {
-        if std::path::Path::new(path).exists() {
-            editors.push(EditorInfo {
-                name: name.to_string(),
-                path: path.to_string(),
-            });
-        }
-    }
-
-    Ok(editors)
+    Ok(launcher::available_editors())
 }
 
 #[tauri::command]
@@ -322,11 +1269,26 @@ async fn set_editor_path(path: String) -> Result<(), String> {
     storage::set_editor_path(&path)
 }
 
+#[tauri::command]
+async fn get_settings() -> Result<storage::Settings, String> {
+    storage::load_settings()
+}
+
+#[tauri::command]
+async fn update_settings(settings: serde_json::Value) -> Result<storage::Settings, String> {
+    storage::update_settings(settings)
+}
+
 #[tauri::command]
 async fn get_saved_connections() -> Result<Vec<Connection>, String> {
     storage::load_connections()
 }
 
+#[tauri::command]
+async fn get_ssh_config_connections() -> Result<Vec<Connection>, String> {
+    storage::load_ssh_config_connections()
+}
+
 #[tauri::command]
 async fn save_connection(connection: HashMap<String, serde_json::Value>) -> Result<(), String> {
     let update_id = connection.get("id").and_then(|v| v.as_str());
@@ -366,9 +1328,56 @@ async fn save_connection(connection: HashMap<String, serde_json::Value>) -> Resu
             .get("share")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        identity_file: connection
+            .get("identityFile")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        proxy_jump: connection
+            .get("proxyJump")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        folder: connection
+            .get("folder")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string()),
+        tags: connection
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str())
+                    .map(|t| t.to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        favorite: connection
+            .get("favorite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        initial_path: get_str(&connection, "initialPath"),
+        local_dir: get_str(&connection, "localDir"),
+        transfer_mode: get_str(&connection, "transferMode"),
+        ftp_mode: get_str(&connection, "ftpMode"),
+        ftp_nat_workaround: connection
+            .get("ftpNatWorkaround")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        charset: get_str(&connection, "charset"),
+        timeout_secs: connection
+            .get("timeoutSecs")
+            .and_then(|v| v.as_u64())
+            .filter(|secs| *secs > 0),
+        ..Default::default()
+    };
+
+    let provided = storage::ProvidedFields {
+        folder: connection.contains_key("folder"),
+        tags: connection.contains_key("tags"),
+        favorite: connection.contains_key("favorite"),
     };
 
-    storage::save_connection(conn, update_id)
+    storage::save_connection(conn, update_id, provided)
 }
 
 #[tauri::command]
@@ -377,13 +1386,133 @@ async fn delete_connection(id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn export_bookmarks(file_path: String, data: String) -> Result<(), String> {
-    std::fs::write(&file_path, data).map_err(|e| format!("파일 저장 실패: {}", e))
+async fn get_bookmark_folders() -> Result<Vec<String>, String> {
+    storage::load_folders()
+}
+
+#[tauri::command]
+async fn create_bookmark_folder(folder: String) -> Result<(), String> {
+    storage::create_folder(&folder)
 }
 
 #[tauri::command]
-async fn import_bookmarks(file_path: String) -> Result<String, String> {
-    std::fs::read_to_string(&file_path).map_err(|e| format!("파일 읽기 실패: {}", e))
+async fn rename_bookmark_folder(from: String, to: String) -> Result<(), String> {
+    storage::rename_folder(&from, &to)
+}
+
+#[tauri::command]
+async fn delete_bookmark_folder(folder: String) -> Result<(), String> {
+    storage::delete_folder(&folder)
+}
+
+#[tauri::command]
+async fn move_bookmarks(ids: Vec<String>, folder: Option<String>) -> Result<(), String> {
+    storage::move_connections(&ids, folder.as_deref())
+}
+
+#[tauri::command]
+async fn set_bookmark_favorite(id: String, favorite: bool) -> Result<(), String> {
+    storage::set_favorite(&id, favorite)
+}
+
+#[tauri::command]
+async fn set_bookmark_tags(id: String, tags: Vec<String>) -> Result<(), String> {
+    storage::set_tags(&id, tags)
+}
+
+#[tauri::command]
+async fn get_bookmark_tags() -> Result<Vec<String>, String> {
+    storage::load_tags()
+}
+
+#[tauri::command]
+async fn find_bookmarks_by_tag(tag: String) -> Result<Vec<Connection>, String> {
+    storage::find_by_tag(&tag)
+}
+
+#[tauri::command]
+async fn export_bookmarks(file_path: String, passphrase: Option<String>) -> Result<(), String> {
+    let connections = storage::load_connections()?;
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    bookmark_file::export_to(&file_path, &connections, passphrase.as_deref())
+}
+
+#[tauri::command]
+async fn import_bookmarks(
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<bookmark_import::ImportReport, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let report = bookmark_file::import_from(&file_path, passphrase.as_deref())?;
+    storage::merge_connections(report.imported.clone())?;
+    Ok(report)
+}
+
+#[tauri::command]
+async fn import_external_bookmarks(
+    file_path: String,
+    source: String,
+) -> Result<bookmark_import::ImportReport, String> {
+    let report = bookmark_import::import_from(&source, &file_path)?;
+    storage::merge_connections(report.imported.clone())?;
+    Ok(report)
+}
+
+// 서버 압축 명령은 출력이 거의 없으므로 오류 메시지 정도만 받는다
+const SERVER_EXEC_OUTPUT_LIMIT: usize = 64 * 1024;
+const SERVER_TEMP_DIR_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+// 서버 압축이 이보다 오래 걸리면 포기하고 내려받으면서 압축한다
+const SERVER_COMPRESS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// SFTP에서는 서버에서 바로 압축해 아카이브 하나만 내려받는다. 작은 파일이 많은 폴더에서 훨씬 빠르다.
+/// exec이 막혀 있거나 서버에 tar/zip이 없으면 false를 돌려주고, 호출한 쪽은 기존 방식으로 압축한다.
+fn compress_on_server(state: &AppState, remote_path: &str, local_path: &str, format: &str) -> bool {
+    let Some(archive_format) = archive::ArchiveFormat::from_extension(format) else {
+        return false;
+    };
+
+    let Ok(mut conn_guard) = state.connection.lock() else {
+        return false;
+    };
+    let ActiveConnection::Sftp(sftp) = &mut *conn_guard else {
+        return false;
+    };
+
+    // 아카이브는 mktemp로 만든 전용 폴더 안에 만들어 이름을 추측하거나 링크로 가로챌 수 없게 한다
+    let Some(remote_dir) = sftp
+        .exec(
+            archive::SERVER_TEMP_DIR_COMMAND,
+            SERVER_EXEC_OUTPUT_LIMIT,
+            Some(SERVER_TEMP_DIR_TIMEOUT),
+        )
+        .ok()
+        .filter(|output| output.exit_status == 0)
+        .and_then(|output| archive::parse_server_temp_dir(&output.stdout))
+    else {
+        return false;
+    };
+    let remote_archive = format!("{}/archive.{}", remote_dir, format);
+
+    let downloaded = archive::server_compress_command(remote_path, archive_format, &remote_archive)
+        .is_some_and(|command| {
+            sftp.exec(
+                &command,
+                SERVER_EXEC_OUTPUT_LIMIT,
+                Some(SERVER_COMPRESS_TIMEOUT),
+            )
+            .is_ok_and(|output| output.exit_status == 0)
+        })
+        && sftp.download(&remote_archive, local_path).is_ok();
+
+    // 압축이 실패하거나 시간을 넘겼어도 만들다 만 아카이브와 임시 폴더가 서버에 남지 않도록 지운다
+    let _ = sftp.delete(&remote_archive, false);
+    let _ = sftp.delete(&remote_dir, true);
+
+    if !downloaded {
+        std::fs::remove_file(local_path).ok();
+    }
+
+    downloaded
 }
 
 #[tauri::command]
@@ -393,191 +1522,486 @@ async fn compress_file(
     format: String,
     is_directory: bool,
     state: State<'_, AppState>,
+) -> Result<archive::CompressSummary, String> {
+    if compress_on_server(&state, &remote_path, &local_path, &format) {
+        return Ok(archive::CompressSummary::default());
+    }
+
+    let archive_format = archive::ArchiveFormat::from_extension(&format)
+        .ok_or_else(|| format!("지원하지 않는 형식: {}", format))?;
+
+    let result = write_remote_archive(
+        &state.connection,
+        &remote_path,
+        &local_path,
+        archive_format,
+        is_directory,
+    );
+
+    // 실패하면 만들다 만 아카이브를 남기지 않는다
+    if result.is_err() {
+        std::fs::remove_file(&local_path).ok();
+    }
+
+    result
+}
+
+fn read_remote_with(
+    conn: &mut ActiveConnection,
+    remote_path: &str,
+    read: &mut dyn FnMut(&mut dyn std::io::Read) -> std::io::Result<()>,
 ) -> Result<(), String> {
-    use std::fs::File;
-    use std::io::{Read, Write};
-    use std::path::Path;
-    
-    let temp_dir = std::env::temp_dir().join(format!("easyftp_compress_{}", uuid_simple()));
-    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("임시 폴더 생성 실패: {}", e))?;
-    
-    let file_name = Path::new(&remote_path)
-        .file_name()
-        .and_then(|n| n.to_str())
+    match conn {
+        ActiveConnection::Ftp(ftp) => ftp.read_with(remote_path, read),
+        ActiveConnection::Sftp(sftp) => sftp.read_with(remote_path, read),
+        ActiveConnection::Smb(smb) => smb.read_with(remote_path, read),
+        ActiveConnection::None => Err("연결되어 있지 않습니다".to_string()),
+    }
+}
+
+/// 목록을 읽은 뒤 파일이 커져서 앞부분만 담았으면 true를 돌려준다
+fn add_remote_file(
+    conn: &mut ActiveConnection,
+    writer: &mut archive::ArchiveWriter,
+    entry: &FileEntry,
+    name: &str,
+) -> Result<bool, String> {
+    // FTP에서 SIZE를 지원하지 않으면 크기가 0으로 오므로 모르는 크기로 다룬다
+    let size = Some(entry.size).filter(|s| *s > 0);
+    let modified = search::parse_time(&entry.modified);
+
+    let mut grown = false;
+    read_remote_with(conn, &entry.path, &mut |reader| {
+        grown = writer.add_file(name, size, modified, reader)?;
+        Ok(())
+    })?;
+
+    Ok(grown)
+}
+
+/// 원격 파일을 하나씩 읽어 바로 아카이브에 써 넣는다. 로컬에 트리를 내려받지 않는다.
+fn write_remote_archive(
+    connection: &Mutex<ActiveConnection>,
+    remote_path: &str,
+    local_path: &str,
+    format: archive::ArchiveFormat,
+    is_directory: bool,
+) -> Result<archive::CompressSummary, String> {
+    let root = remote_path.trim_end_matches('/');
+    let base_name = root
+        .rsplit('/')
+        .next()
+        .filter(|n| !n.is_empty())
         .unwrap_or("file");
-    let temp_path = temp_dir.join(file_name);
-    
-    {
-        let mut conn_guard = state.connection.lock().map_err(|e| e.to_string())?;
-        
-        if is_directory {
-            std::fs::create_dir_all(&temp_path).map_err(|e| format!("폴더 생성 실패: {}", e))?;
-            download_directory_recursive(&mut conn_guard, &remote_path, &temp_path)?;
-        } else {
-            match &mut *conn_guard {
-                ActiveConnection::None => return Err("연결되어 있지 않습니다".to_string()),
-                ActiveConnection::Ftp(ftp) => ftp.download(&remote_path, temp_path.to_str().unwrap())?,
-                ActiveConnection::Sftp(sftp) => sftp.download(&remote_path, temp_path.to_str().unwrap())?,
-                ActiveConnection::Smb(smb) => smb.download(&remote_path, temp_path.to_str().unwrap())?,
-            }
+
+    let mut writer = archive::ArchiveWriter::create(std::path::Path::new(local_path), format)?;
+    let mut summary = archive::CompressSummary::default();
+
+    if is_directory {
+        writer.add_directory(base_name, None)?;
+
+        walk_remote(
+            connection,
+            remote_path,
+            true,
+            None,
+            &mut summary.unreadable,
+            &mut |connection, entry| {
+                let relative = entry
+                    .path
+                    .strip_prefix(root)
+                    .unwrap_or(&entry.name)
+                    .trim_start_matches('/');
+                let name = format!("{}/{}", base_name, relative);
+
+                if entry.is_directory {
+                    writer.add_directory(&name, search::parse_time(&entry.modified))?;
+                } else {
+                    let mut conn = connection.lock().map_err(|e| e.to_string())?;
+                    if add_remote_file(&mut conn, &mut writer, entry, &name)? {
+                        summary.changed.push(entry.path.clone());
+                    }
+                }
+
+                Ok(true)
+            },
+        )?;
+    } else {
+        let mut conn = connection.lock().map_err(|e| e.to_string())?;
+        let entry = stat_remote(&mut conn, remote_path)?;
+        if add_remote_file(&mut conn, &mut writer, &entry, base_name)? {
+            summary.changed.push(entry.path);
         }
     }
-    
-    match format.as_str() {
-        "zip" => compress_zip(&temp_path, &local_path)?,
-        "tar" => compress_tar(&temp_path, &local_path, None)?,
-        "tar.gz" => compress_tar(&temp_path, &local_path, Some("gz"))?,
-        "tar.bz2" => compress_tar(&temp_path, &local_path, Some("bz2"))?,
-        _ => return Err(format!("지원하지 않는 형식: {}", format)),
+
+    writer.finish()?;
+    Ok(summary)
+}
+
+type WalkVisitor<'a> = dyn FnMut(&Mutex<ActiveConnection>, &FileEntry) -> Result<bool, String> + 'a;
+
+fn list_remote(
+    connection: &Mutex<ActiveConnection>,
+    remote_path: &str,
+    show_hidden: bool,
+) -> Result<Vec<FileEntry>, String> {
+    let mut conn = connection.lock().map_err(|e| e.to_string())?;
+    match &mut *conn {
+        ActiveConnection::None => Err("연결되어 있지 않습니다".to_string()),
+        ActiveConnection::Ftp(ftp) => ftp.list_files(remote_path, show_hidden),
+        ActiveConnection::Sftp(sftp) => sftp.list_files(remote_path, show_hidden),
+        ActiveConnection::Smb(smb) => smb.list_files(remote_path, show_hidden),
     }
-    
-    std::fs::remove_dir_all(&temp_dir).ok();
-    
-    Ok(())
 }
 
-fn download_directory_recursive(
-    conn: &mut ActiveConnection,
+/// 원격 트리를 깊이 우선으로 순회한다. 폴더는 하위 항목보다 먼저 방문하며,
+/// visit가 false를 반환하면 순회를 멈추고 false를 돌려준다.
+/// 시작 폴더를 읽지 못하면 실패하고, 권한 등으로 읽지 못한 하위 폴더는 unreadable에 모으고 건너뛴다.
+/// 연결은 폴더를 읽거나 파일을 처리할 때만 잠그므로 긴 순회 중에도 다른 명령이 끼어들 수 있다.
+fn walk_remote(
+    connection: &Mutex<ActiveConnection>,
     remote_path: &str,
-    local_path: &std::path::Path,
-) -> Result<(), String> {
-    let entries = match conn {
-        ActiveConnection::None => return Err("연결되어 있지 않습니다".to_string()),
-        ActiveConnection::Ftp(ftp) => ftp.list_files(remote_path)?,
-        ActiveConnection::Sftp(sftp) => sftp.list_files(remote_path)?,
-        ActiveConnection::Smb(smb) => smb.list_files(remote_path)?,
-    };
-    
+    show_hidden: bool,
+    max_depth: Option<usize>,
+    unreadable: &mut Vec<String>,
+    visit: &mut WalkVisitor<'_>,
+) -> Result<bool, String> {
+    let entries = list_remote(connection, remote_path, show_hidden)?;
+    walk_entries(
+        connection,
+        entries,
+        show_hidden,
+        1,
+        max_depth,
+        unreadable,
+        visit,
+    )
+}
+
+fn walk_entries(
+    connection: &Mutex<ActiveConnection>,
+    entries: Vec<FileEntry>,
+    show_hidden: bool,
+    depth: usize,
+    max_depth: Option<usize>,
+    unreadable: &mut Vec<String>,
+    visit: &mut WalkVisitor<'_>,
+) -> Result<bool, String> {
     for entry in entries {
-        let entry_local_path = local_path.join(&entry.name);
-        
-        if entry.is_directory {
-            std::fs::create_dir_all(&entry_local_path)
-                .map_err(|e| format!("폴더 생성 실패: {}", e))?;
-            download_directory_recursive(conn, &entry.path, &entry_local_path)?;
-        } else {
-            match conn {
-                ActiveConnection::None => return Err("연결되어 있지 않습니다".to_string()),
-                ActiveConnection::Ftp(ftp) => ftp.download(&entry.path, entry_local_path.to_str().unwrap())?,
-                ActiveConnection::Sftp(sftp) => sftp.download(&entry.path, entry_local_path.to_str().unwrap())?,
-                ActiveConnection::Smb(smb) => smb.download(&entry.path, entry_local_path.to_str().unwrap())?,
+        if !visit(connection, &entry)? {
+            return Ok(false);
+        }
+
+        let descend = max_depth.is_none_or(|max| depth < max);
+        if !entry.is_directory || !descend {
+            continue;
+        }
+
+        let children = match list_remote(connection, &entry.path, show_hidden) {
+            Ok(children) => children,
+            Err(e) => {
+                // 순회 중에 연결이 끊겼다면 나머지 폴더도 읽을 수 없다
+                if matches!(
+                    *connection.lock().map_err(|e| e.to_string())?,
+                    ActiveConnection::None
+                ) {
+                    return Err(e);
+                }
+                unreadable.push(entry.path.clone());
+                continue;
             }
+        };
+
+        if !walk_entries(
+            connection,
+            children,
+            show_hidden,
+            depth + 1,
+            max_depth,
+            unreadable,
+            visit,
+        )? {
+            return Ok(false);
         }
     }
-    
-    Ok(())
+
+    Ok(true)
 }
 
-fn compress_zip(source: &std::path::Path, dest: &str) -> Result<(), String> {
-    use std::fs::File;
-    use std::io::{Read, Write};
-    use walkdir::WalkDir;
-    use zip::write::FileOptions;
-    use zip::ZipWriter;
-    
-    let file = File::create(dest).map_err(|e| format!("파일 생성 실패: {}", e))?;
-    let mut zip = ZipWriter::new(file);
-    let options = FileOptions::<()>::default()
-        .compression_method(zip::CompressionMethod::Deflated);
-    
-    if source.is_file() {
-        let name = source.file_name().and_then(|n| n.to_str()).unwrap_or("file");
-        zip.start_file(name, options).map_err(|e| format!("ZIP 파일 추가 실패: {}", e))?;
-        let mut f = File::open(source).map_err(|e| format!("파일 열기 실패: {}", e))?;
-        let mut buffer = Vec::new();
-        f.read_to_end(&mut buffer).map_err(|e| format!("파일 읽기 실패: {}", e))?;
-        zip.write_all(&buffer).map_err(|e| format!("파일 쓰기 실패: {}", e))?;
-    } else {
-        let base_name = source.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        
-        for entry in WalkDir::new(source) {
-            let entry = entry.map_err(|e| format!("디렉토리 탐색 실패: {}", e))?;
-            let path = entry.path();
-            let relative = path.strip_prefix(source.parent().unwrap_or(source))
-                .map_err(|e| format!("경로 처리 실패: {}", e))?;
-            
-            if path.is_file() {
-                zip.start_file(relative.to_string_lossy(), options)
-                    .map_err(|e| format!("ZIP 파일 추가 실패: {}", e))?;
-                let mut f = File::open(path).map_err(|e| format!("파일 열기 실패: {}", e))?;
-                let mut buffer = Vec::new();
-                f.read_to_end(&mut buffer).map_err(|e| format!("파일 읽기 실패: {}", e))?;
-                zip.write_all(&buffer).map_err(|e| format!("파일 쓰기 실패: {}", e))?;
-            } else if path.is_dir() && path != source {
-                zip.add_directory(relative.to_string_lossy(), options)
-                    .map_err(|e| format!("ZIP 디렉토리 추가 실패: {}", e))?;
+#[tauri::command]
+async fn search_remote(
+    path: String,
+    options: search::SearchOptions,
+    search_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<search::SearchSummary, String> {
+    let filter = search::SearchFilter::new(&options)?;
+    let show_hidden = resolve_show_hidden(options.show_hidden)?;
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    state
+        .searches
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(search_id.clone(), cancelled.clone());
+
+    let mut summary = search::SearchSummary {
+        search_id: search_id.clone(),
+        matched: 0,
+        scanned: 0,
+        unreadable: 0,
+        cancelled: false,
+    };
+    let mut pending: Vec<FileEntry> = Vec::new();
+    let mut unreadable = Vec::new();
+
+    let result = walk_remote(
+        &state.connection,
+        &path,
+        show_hidden,
+        options.max_depth,
+        &mut unreadable,
+        &mut |_, entry| {
+            if cancelled.load(Ordering::Relaxed) {
+                return Ok(false);
             }
-        }
+
+            summary.scanned += 1;
+            if filter.matches(entry) {
+                summary.matched += 1;
+                pending.push(entry.clone());
+            }
+
+            // 찾는 대로 보내되, 이벤트가 너무 잦지 않도록 묶어서 보낸다
+            if pending.len() >= 100 || (entry.is_directory && !pending.is_empty()) {
+                let _ = app.emit(
+                    "search-result",
+                    search::SearchResult {
+                        search_id: search_id.clone(),
+                        entries: std::mem::take(&mut pending),
+                    },
+                );
+            }
+
+            Ok(true)
+        },
+    );
+
+    state
+        .searches
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&search_id);
+
+    if !pending.is_empty() {
+        let _ = app.emit(
+            "search-result",
+            search::SearchResult {
+                search_id: search_id.clone(),
+                entries: pending,
+            },
+        );
     }
-    
-    zip.finish().map_err(|e| format!("ZIP 완료 실패: {}", e))?;
-    Ok(())
+
+    summary.cancelled = !result?;
+    summary.unreadable = unreadable.len();
+    Ok(summary)
 }
 
-fn compress_tar(source: &std::path::Path, dest: &str, compression: Option<&str>) -> Result<(), String> {
-    use std::fs::File;
-    use tar::Builder;
-    
-    let file = File::create(dest).map_err(|e| format!("파일 생성 실패: {}", e))?;
-    
-    let name = source.file_name().and_then(|n| n.to_str()).unwrap_or("archive");
-    
-    match compression {
-        Some("gz") => {
-            use flate2::write::GzEncoder;
-            use flate2::Compression;
-            let encoder = GzEncoder::new(file, Compression::default());
-            let mut tar = Builder::new(encoder);
-            if source.is_file() {
-                tar.append_path_with_name(source, name)
-                    .map_err(|e| format!("TAR 파일 추가 실패: {}", e))?;
-            } else {
-                tar.append_dir_all(name, source)
-                    .map_err(|e| format!("TAR 디렉토리 추가 실패: {}", e))?;
+#[tauri::command]
+async fn remote_grep(
+    path: String,
+    query: String,
+    options: grep::GrepOptions,
+    search_id: String,
+    state: State<'_, AppState>,
+) -> Result<grep::GrepReport, String> {
+    if query.is_empty() {
+        return Err("검색어를 입력해주세요".to_string());
+    }
+
+    // cancel_search로 취소할 수 있도록 검색과 같은 목록에 등록한다
+    let cancelled = Arc::new(AtomicBool::new(false));
+    state
+        .searches
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(search_id.clone(), cancelled.clone());
+
+    let result = grep_remote(&state, &path, &query, &options, &cancelled);
+
+    state
+        .searches
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&search_id);
+
+    result
+}
+
+/// 서버에서 grep을 실행한다. 시간이 다 되거나 취소되면 그때까지 받은 결과만 쓴다.
+/// exec을 쓸 수 없거나 출력을 해석할 수 없으면 None이다.
+fn grep_exec(
+    state: &AppState,
+    command: &str,
+    max_results: usize,
+    cancelled: &AtomicBool,
+) -> Option<grep::GrepReport> {
+    let deadline = std::time::Instant::now() + grep::EXEC_TIMEOUT;
+    let full = std::cell::Cell::new(false);
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+
+    let status = run_remote_exec(
+        state,
+        command,
+        &|| {
+            full.get() || cancelled.load(Ordering::Relaxed) || std::time::Instant::now() >= deadline
+        },
+        &mut |stream, data| {
+            let buffer = match stream {
+                sftp_client::ExecStream::Stdout => &mut stdout,
+                sftp_client::ExecStream::Stderr => &mut stderr,
+            };
+            let room = grep::EXEC_OUTPUT_LIMIT.saturating_sub(buffer.len());
+            buffer.extend_from_slice(&data[..data.len().min(room)]);
+            if data.len() > room {
+                full.set(true);
             }
-            tar.finish().map_err(|e| format!("TAR 완료 실패: {}", e))?;
+        },
+    )
+    .ok()?;
+
+    let output = sftp_client::ExecOutput {
+        stdout,
+        stderr,
+        truncated: status.is_none(),
+        exit_status: status.unwrap_or(-1),
+    };
+    grep::parse_exec_output(&output, max_results)
+}
+
+fn grep_remote(
+    state: &AppState,
+    path: &str,
+    query: &str,
+    options: &grep::GrepOptions,
+    cancelled: &AtomicBool,
+) -> Result<grep::GrepReport, String> {
+    let matcher = grep::LineMatcher::new(query, options)?;
+    let show_hidden = resolve_show_hidden(options.show_hidden)?;
+    let max_results = options.max_results();
+
+    // exec 채널을 쓸 수 없는 서버(SFTP 전용 계정 등)는 내려받아 검색한다
+    let is_sftp = matches!(
+        &*state.connection.lock().map_err(|e| e.to_string())?,
+        ActiveConnection::Sftp(_)
+    );
+    if is_sftp {
+        let command = grep::build_command(path, query, options);
+        if let Some(report) = grep_exec(state, &command, max_results, cancelled) {
+            return Ok(report);
         }
-        Some("bz2") => {
-            use bzip2::write::BzEncoder;
-            use bzip2::Compression;
-            let encoder = BzEncoder::new(file, Compression::default());
-            let mut tar = Builder::new(encoder);
-            if source.is_file() {
-                tar.append_path_with_name(source, name)
-                    .map_err(|e| format!("TAR 파일 추가 실패: {}", e))?;
-            } else {
-                tar.append_dir_all(name, source)
-                    .map_err(|e| format!("TAR 디렉토리 추가 실패: {}", e))?;
+    }
+
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(grep::GrepReport {
+            matches: Vec::new(),
+            method: grep::GrepMethod::Exec,
+            truncated: true,
+            skipped: Vec::new(),
+        });
+    }
+
+    let mut matches = Vec::new();
+    let mut skipped = Vec::new();
+    let mut unreadable = Vec::new();
+
+    let complete = walk_remote(
+        &state.connection,
+        path,
+        show_hidden,
+        None,
+        &mut unreadable,
+        &mut |connection, entry| {
+            if cancelled.load(Ordering::Relaxed) {
+                return Ok(false);
             }
-            tar.finish().map_err(|e| format!("TAR 완료 실패: {}", e))?;
-        }
-        None => {
-            let mut tar = Builder::new(file);
-            if source.is_file() {
-                tar.append_path_with_name(source, name)
-                    .map_err(|e| format!("TAR 파일 추가 실패: {}", e))?;
-            } else {
-                tar.append_dir_all(name, source)
-                    .map_err(|e| format!("TAR 디렉토리 추가 실패: {}", e))?;
+            if entry.is_directory || !matcher.includes(&entry.name) {
+                return Ok(true);
             }
-            tar.finish().map_err(|e| format!("TAR 완료 실패: {}", e))?;
-        }
-        _ => return Err("지원하지 않는 압축 형식".to_string()),
+            if entry.size > grep::SCAN_SIZE_LIMIT {
+                skipped.push(entry.path.clone());
+                return Ok(true);
+            }
+
+            let data = match &mut *connection.lock().map_err(|e| e.to_string())? {
+                ActiveConnection::None => return Err("연결되어 있지 않습니다".to_string()),
+                ActiveConnection::Ftp(ftp) => ftp.read_file(&entry.path),
+                ActiveConnection::Sftp(sftp) => sftp.read_file(&entry.path),
+                ActiveConnection::Smb(smb) => smb.read_file(&entry.path),
+            };
+
+            match data {
+                Ok(data) => Ok(matcher.scan(&entry.path, &data, max_results, &mut matches)),
+                Err(_) => {
+                    skipped.push(entry.path.clone());
+                    Ok(true)
+                }
+            }
+        },
+    )?;
+
+    // 읽지 못한 폴더도 검색하지 못한 경로로 알려준다
+    skipped.extend(unreadable);
+
+    Ok(grep::GrepReport {
+        matches,
+        method: grep::GrepMethod::Scan,
+        truncated: !complete,
+        skipped,
+    })
+}
+
+#[tauri::command]
+async fn cancel_search(search_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(flag) = state
+        .searches
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&search_id)
+    {
+        flag.store(true, Ordering::Relaxed);
     }
-    
     Ok(())
 }
 
 fn uuid_simple() -> String {
+    use std::sync::atomic::AtomicU64;
     use std::time::{SystemTime, UNIX_EPOCH};
+    // 가져오기처럼 짧은 시간에 여러 번 불려도 겹치지 않도록 순번을 붙인다
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
     let duration = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default();
-    format!("{:x}{:x}", duration.as_secs(), duration.subsec_nanos())
+    format!(
+        "{:x}{:x}-{:x}",
+        duration.as_secs(),
+        duration.subsec_nanos(),
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    )
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    temp_files::lock_instance();
+    std::thread::spawn(|| {
+        temp_files::cleanup_stale();
+        thumbnail::cleanup_cache();
+    });
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -587,22 +2011,54 @@ pub fn run() {
             connect,
             disconnect,
             list_files,
+            list_files_page,
+            list_files_stream,
             download_file,
             upload_file,
             delete_file,
+            rename_file,
+            clear_listing_cache,
             preview_file_base64,
             preview_file_text,
+            preview_thumbnail,
+            preview_archive,
+            extract_archive,
             open_with_editor,
             open_with_default_app,
+            get_edited_files,
+            upload_edited_file,
+            stop_editing,
             get_available_editors,
             get_editor_path,
             set_editor_path,
+            get_settings,
+            update_settings,
             get_saved_connections,
+            get_ssh_config_connections,
             save_connection,
             delete_connection,
+            get_bookmark_folders,
+            create_bookmark_folder,
+            rename_bookmark_folder,
+            delete_bookmark_folder,
+            move_bookmarks,
+            set_bookmark_favorite,
+            set_bookmark_tags,
+            get_bookmark_tags,
+            find_bookmarks_by_tag,
             export_bookmarks,
             import_bookmarks,
+            import_external_bookmarks,
             compress_file,
+            search_remote,
+            cancel_search,
+            remote_grep,
+            exec_command,
+            cancel_exec,
+            open_shell,
+            write_shell,
+            resize_shell,
+            close_shell,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
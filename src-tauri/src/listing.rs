@@ -0,0 +1,400 @@
+use crate::FileEntry;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub const CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Mtime,
+    Type,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListOptions {
+    pub sort_by: SortKey,
+    pub descending: bool,
+    pub filter: Option<String>,
+    pub regex: bool,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPage {
+    pub entries: Vec<FileEntry>,
+    pub total: usize,
+    pub offset: usize,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListChunk {
+    pub request_id: String,
+    pub path: String,
+    pub entries: Vec<FileEntry>,
+    pub offset: usize,
+    pub total: usize,
+    // false면 서버에서 읽는 중인 항목을 받은 순서대로 보낸 것이고,
+    // true면 정렬이 끝난 목록이다. 정렬된 목록은 offset 0부터 다시 보낸다.
+    pub sorted: bool,
+    pub done: bool,
+}
+
+pub enum Matcher {
+    All,
+    Glob(glob::Pattern),
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    pub fn new(filter: Option<&str>, regex: bool) -> Result<Self, String> {
+        let filter = match filter.map(|f| f.trim()) {
+            None | Some("") => return Ok(Matcher::All),
+            Some(filter) => filter,
+        };
+
+        if regex {
+            let regex = regex::RegexBuilder::new(filter)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("잘못된 정규식: {}", e))?;
+            Ok(Matcher::Regex(regex))
+        } else {
+            let pattern = glob::Pattern::new(filter).map_err(|e| format!("잘못된 패턴: {}", e))?;
+            Ok(Matcher::Glob(pattern))
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Matcher::All => true,
+            Matcher::Glob(pattern) => pattern.matches_with(
+                name,
+                glob::MatchOptions {
+                    case_sensitive: false,
+                    require_literal_separator: false,
+                    require_literal_leading_dot: false,
+                },
+            ),
+            Matcher::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+fn extension(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => ext.to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+fn compare(a: &FileEntry, b: &FileEntry, sort_by: SortKey) -> Ordering {
+    let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+    match sort_by {
+        SortKey::Name => by_name(),
+        SortKey::Size => a.size.cmp(&b.size).then_with(by_name),
+        SortKey::Mtime => a.modified.cmp(&b.modified).then_with(by_name),
+        SortKey::Type => extension(&a.name)
+            .cmp(&extension(&b.name))
+            .then_with(by_name),
+    }
+}
+
+pub fn apply(entries: &[FileEntry], options: &ListOptions) -> Result<Vec<FileEntry>, String> {
+    let matcher = Matcher::new(options.filter.as_deref(), options.regex)?;
+
+    let mut result: Vec<FileEntry> = entries
+        .iter()
+        .filter(|e| matcher.matches(&e.name))
+        .cloned()
+        .collect();
+
+    // 정렬 방향과 관계없이 폴더를 먼저 보여준다
+    result.sort_by(|a, b| {
+        b.is_directory.cmp(&a.is_directory).then_with(|| {
+            let ordering = compare(a, b, options.sort_by);
+            if options.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        })
+    });
+
+    Ok(result)
+}
+
+pub fn page(entries: &[FileEntry], offset: usize, limit: Option<usize>) -> ListPage {
+    let total = entries.len();
+    let limit = limit.unwrap_or(total);
+
+    let entries: Vec<FileEntry> = entries.iter().skip(offset).take(limit).cloned().collect();
+    let has_more = offset + entries.len() < total;
+
+    ListPage {
+        entries,
+        total,
+        offset,
+        has_more,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ViewKey {
+    sort_by: SortKey,
+    descending: bool,
+    filter: Option<String>,
+    regex: bool,
+}
+
+impl ViewKey {
+    fn new(options: &ListOptions) -> Self {
+        Self {
+            sort_by: options.sort_by,
+            descending: options.descending,
+            filter: options.filter.clone(),
+            regex: options.regex,
+        }
+    }
+}
+
+struct CachedListing {
+    entries: Vec<FileEntry>,
+    fetched_at: Instant,
+    // 마지막으로 요청된 정렬·필터 결과. 페이지마다 다시 정렬하지 않도록 보관한다
+    view: Option<(ViewKey, Vec<FileEntry>)>,
+}
+
+/// 세션 동안 읽은 디렉토리 목록. 경로와 숨김 파일 표시 여부별로 보관한다.
+#[derive(Default)]
+pub struct ListingCache {
+    entries: HashMap<(String, bool), CachedListing>,
+}
+
+pub fn normalize_path(path: &str) -> String {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        "/".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+pub fn parent_path(path: &str) -> String {
+    let path = normalize_path(path);
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => path[..index].to_string(),
+    }
+}
+
+fn is_within(path: &str, dir: &str) -> bool {
+    path == dir
+        || dir == "/"
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+impl ListingCache {
+    pub fn insert(&mut self, path: &str, show_hidden: bool, entries: Vec<FileEntry>) {
+        self.entries.insert(
+            (normalize_path(path), show_hidden),
+            CachedListing {
+                entries,
+                fetched_at: Instant::now(),
+                view: None,
+            },
+        );
+    }
+
+    /// 캐시된 목록에 정렬·필터를 적용한 결과. 같은 옵션으로 다시 요청하면 저장해 둔 결과를 쓴다.
+    pub fn view(
+        &mut self,
+        path: &str,
+        show_hidden: bool,
+        max_age: Duration,
+        options: &ListOptions,
+    ) -> Option<Result<&[FileEntry], String>> {
+        let cached = self
+            .entries
+            .get_mut(&(normalize_path(path), show_hidden))
+            .filter(|cached| cached.fetched_at.elapsed() <= max_age)?;
+
+        let key = ViewKey::new(options);
+        if cached.view.as_ref().map(|(k, _)| k) != Some(&key) {
+            match apply(&cached.entries, options) {
+                Ok(entries) => cached.view = Some((key, entries)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        cached
+            .view
+            .as_ref()
+            .map(|(_, entries)| Ok(entries.as_slice()))
+    }
+
+    /// 경로가 바뀐 항목의 상위 폴더 목록과, 그 항목 아래의 목록을 모두 비운다
+    pub fn invalidate(&mut self, path: &str) {
+        let path = normalize_path(path);
+        let parent = parent_path(&path);
+
+        self.entries
+            .retain(|(dir, _), _| *dir != parent && !is_within(dir, &path));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_directory: bool, size: u64) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            path: format!("/{}", name),
+            is_directory,
+            size,
+            modified: String::new(),
+            permissions: None,
+        }
+    }
+
+    fn cached_paths(cache: &mut ListingCache, paths: &[&str]) -> Vec<String> {
+        paths
+            .iter()
+            .filter(|path| {
+                cache
+                    .view(path, false, Duration::MAX, &ListOptions::default())
+                    .is_some()
+            })
+            .map(|path| path.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize_path(""), "/");
+        assert_eq!(normalize_path("/"), "/");
+        assert_eq!(normalize_path("/home/user/"), "/home/user");
+        assert_eq!(parent_path("/home/user"), "/home");
+        assert_eq!(parent_path("/home"), "/");
+        assert_eq!(parent_path("/"), "/");
+    }
+
+    #[test]
+    fn invalidate_clears_parent_and_descendants() {
+        let paths = ["/", "/a", "/a/b", "/a/b/c", "/ab", "/x"];
+        let mut cache = ListingCache::default();
+        for path in paths {
+            cache.insert(path, false, Vec::new());
+        }
+
+        cache.invalidate("/a/b/");
+
+        // /ab는 /a로 시작하지만 /a/b 아래가 아니므로 남는다
+        assert_eq!(cached_paths(&mut cache, &paths), ["/", "/ab", "/x"]);
+    }
+
+    #[test]
+    fn invalidate_top_level_entry_clears_root() {
+        let paths = ["/", "/a", "/b"];
+        let mut cache = ListingCache::default();
+        for path in paths {
+            cache.insert(path, false, Vec::new());
+        }
+
+        cache.invalidate("/a");
+
+        assert_eq!(cached_paths(&mut cache, &paths), ["/b"]);
+    }
+
+    #[test]
+    fn invalidate_applies_to_both_hidden_modes() {
+        let mut cache = ListingCache::default();
+        cache.insert("/a", false, Vec::new());
+        cache.insert("/a", true, Vec::new());
+
+        cache.invalidate("/a/file.txt");
+
+        let options = ListOptions::default();
+        assert!(cache.view("/a", false, Duration::MAX, &options).is_none());
+        assert!(cache.view("/a", true, Duration::MAX, &options).is_none());
+    }
+
+    #[test]
+    fn view_sorts_directories_first_and_filters() {
+        let mut cache = ListingCache::default();
+        cache.insert(
+            "/",
+            false,
+            vec![
+                entry("b.txt", false, 10),
+                entry("Docs", true, 0),
+                entry("a.log", false, 30),
+                entry("archive", true, 0),
+            ],
+        );
+
+        let options = ListOptions {
+            sort_by: SortKey::Size,
+            descending: true,
+            ..Default::default()
+        };
+        let names: Vec<String> = cache
+            .view("/", false, Duration::MAX, &options)
+            .unwrap()
+            .unwrap()
+            .iter()
+            .map(|e| e.name.clone())
+            .collect();
+        assert_eq!(names, ["Docs", "archive", "a.log", "b.txt"]);
+
+        let options = ListOptions {
+            filter: Some("*.TXT".to_string()),
+            ..Default::default()
+        };
+        let view = cache
+            .view("/", false, Duration::MAX, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(view.len(), 1);
+        assert_eq!(view[0].name, "b.txt");
+    }
+
+    #[test]
+    fn view_respects_max_age() {
+        let mut cache = ListingCache::default();
+        cache.insert("/", false, Vec::new());
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert!(cache
+            .view("/", false, Duration::ZERO, &ListOptions::default())
+            .is_none());
+    }
+
+    #[test]
+    fn pages_entries() {
+        let entries: Vec<FileEntry> = (0..5).map(|i| entry(&i.to_string(), false, 0)).collect();
+
+        let page = page(&entries, 3, Some(10));
+        assert_eq!(page.total, 5);
+        assert_eq!(page.entries.len(), 2);
+        assert!(!page.has_more);
+    }
+}
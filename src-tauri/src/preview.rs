@@ -0,0 +1,339 @@
+use encoding_rs::{DecoderResult, Encoding, EUC_KR, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::Serialize;
+
+// 텍스트 미리보기는 앞(또는 뒤) 256KB만 가져온다
+pub const TEXT_PREVIEW_BYTES: u64 = 256 * 1024;
+// 이미지·PDF 미리보기는 파일 전체가 필요하므로 이보다 크면 열지 않는다
+pub const BINARY_PREVIEW_LIMIT: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    Mixed,
+    None,
+}
+
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: &'static Encoding,
+    pub had_errors: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextPreview {
+    pub text: String,
+    pub encoding: String,
+    pub had_errors: bool,
+    pub line_ending: LineEnding,
+    pub offset: u64,
+    pub total_size: u64,
+    pub truncated: bool,
+}
+
+/// 읽을 구간을 정한다. tail이면 파일 끝에서 max_bytes만큼, 아니면 처음부터 읽는다.
+pub fn range_for(total_size: u64, max_bytes: u64, tail: bool) -> (u64, u64) {
+    if tail {
+        let offset = total_size.saturating_sub(max_bytes);
+        (offset, total_size - offset)
+    } else {
+        (0, max_bytes)
+    }
+}
+
+/// UTF-16은 ASCII 문자마다 0이 끼므로, 한쪽 자리에만 0이 몰려 있으면 UTF-16으로 본다
+fn detect_utf16(data: &[u8]) -> Option<&'static Encoding> {
+    let sample = &data[..data.len().min(4096)];
+    if sample.len() < 4 {
+        return None;
+    }
+
+    let (mut even, mut odd) = (0usize, 0usize);
+    for (index, byte) in sample.iter().enumerate() {
+        if *byte == 0 {
+            if index % 2 == 0 {
+                even += 1;
+            } else {
+                odd += 1;
+            }
+        }
+    }
+
+    let half = sample.len() / 2;
+    if odd > half * 3 / 10 && even < half / 20 {
+        Some(UTF_16LE)
+    } else if even > half * 3 / 10 && odd < half / 20 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// CP949로 오류 없이 디코딩되고 ASCII가 아닌 문자 대부분이 한글이면 CP949로 본다.
+/// 끝이 잘렸다면 마지막의 불완전한 문자는 오류로 보지 않는다.
+fn looks_like_cp949(data: &[u8], cut_end: bool) -> bool {
+    let mut decoder = EUC_KR.new_decoder_without_bom_handling();
+    let capacity = decoder
+        .max_utf8_buffer_length_without_replacement(data.len())
+        .unwrap_or(data.len() * 3);
+    let mut text = String::with_capacity(capacity);
+    let (result, _) = decoder.decode_to_string_without_replacement(data, &mut text, !cut_end);
+    if result != DecoderResult::InputEmpty {
+        return false;
+    }
+
+    let non_ascii: Vec<char> = text.chars().filter(|c| !c.is_ascii()).collect();
+    let hangul = non_ascii
+        .iter()
+        .filter(|c| ('\u{AC00}'..='\u{D7A3}').contains(*c))
+        .count();
+
+    !non_ascii.is_empty() && hangul * 10 >= non_ascii.len() * 7
+}
+
+/// 중간에서 읽기 시작했다면 첫 바이트가 문자 중간일 수 있으므로 첫 줄을 버린 부분으로 추정한다.
+/// 줄바꿈이 없으면 앞쪽의 UTF-8 연속 바이트만 버린다.
+fn detection_sample(data: &[u8], offset: u64) -> &[u8] {
+    if offset == 0 {
+        return data;
+    }
+
+    match data.iter().position(|byte| *byte == b'\n') {
+        Some(newline) if newline + 1 < data.len() => &data[newline + 1..],
+        _ => {
+            let skip = data
+                .iter()
+                .take(3)
+                .take_while(|byte| (0x80..=0xBF).contains(*byte))
+                .count();
+            &data[skip..]
+        }
+    }
+}
+
+fn detect_encoding(
+    data: &[u8],
+    offset: u64,
+    cut_end: bool,
+) -> Result<(&'static Encoding, usize), String> {
+    if offset == 0 {
+        if let Some((encoding, bom_length)) = Encoding::for_bom(data) {
+            return Ok((encoding, bom_length));
+        }
+    }
+
+    if let Some(encoding) = detect_utf16(data) {
+        return Ok((encoding, 0));
+    }
+
+    if data.contains(&0) {
+        return Err("바이너리 파일은 텍스트로 미리볼 수 없습니다".to_string());
+    }
+
+    let sample = detection_sample(data, offset);
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => return Ok((UTF_8, 0)),
+        // 끝이 잘려 마지막 문자만 불완전한 경우도 UTF-8로 본다
+        Err(e) if cut_end && e.error_len().is_none() => return Ok((UTF_8, 0)),
+        Err(_) => {}
+    }
+
+    // 줄바꿈 없이 중간에서 시작했다면 첫 바이트가 CP949 두 번째 바이트일 수 있다
+    let starts_mid_line = offset > 0 && sample.len() == data.len();
+    if looks_like_cp949(sample, cut_end)
+        || (starts_mid_line && !sample.is_empty() && looks_like_cp949(&sample[1..], cut_end))
+    {
+        return Ok((EUC_KR, 0));
+    }
+
+    // windows-1252는 모든 바이트를 해석할 수 있어 Latin-1 계열의 마지막 선택지가 된다
+    Ok((WINDOWS_1252, 0))
+}
+
+/// 끝이 잘린 데이터를 디코딩한다. CP949처럼 마지막에 첫 바이트만 남은 문자는 버린다.
+fn decode_cut(encoding: &'static Encoding, data: &[u8]) -> (String, bool) {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let capacity = decoder
+        .max_utf8_buffer_length(data.len())
+        .unwrap_or(data.len() * 3);
+    let mut text = String::with_capacity(capacity);
+    // last가 false면 불완전한 마지막 문자는 오류로 보지 않고 디코더에 남긴다
+    let (_, _, had_errors) = decoder.decode_to_string(data, &mut text, false);
+    (text, had_errors)
+}
+
+/// 잘라 읽은 바이트를 문자열로 바꾼다. 인코딩을 지정하지 않으면 BOM과 내용으로 추정한다.
+/// 중간에서 시작했다면 첫 번째 불완전한 줄을, 끝이 잘렸다면 마지막의 불완전한 문자를 버린다.
+pub fn decode_text(
+    data: &[u8],
+    offset: u64,
+    cut_end: bool,
+    charset: Option<&str>,
+) -> Result<DecodedText, String> {
+    let (encoding, bom_length) = match charset.filter(|c| !c.is_empty()) {
+        Some(label) => {
+            let encoding = Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| format!("지원하지 않는 문자셋: {}", label))?;
+            (encoding, 0)
+        }
+        None => detect_encoding(data, offset, cut_end)?,
+    };
+
+    let mut data = &data[bom_length..];
+
+    let is_utf16 = encoding == UTF_16LE || encoding == UTF_16BE;
+    if is_utf16 {
+        if offset % 2 == 1 && !data.is_empty() {
+            data = &data[1..];
+        }
+        if cut_end && data.len() % 2 == 1 {
+            data = &data[..data.len() - 1];
+        }
+    } else if cut_end && encoding == UTF_8 {
+        if let Err(e) = std::str::from_utf8(data) {
+            if e.error_len().is_none() {
+                data = &data[..e.valid_up_to()];
+            }
+        }
+    }
+
+    let (mut text, had_errors) = if cut_end {
+        decode_cut(encoding, data)
+    } else {
+        let (text, had_errors) = encoding.decode_without_bom_handling(data);
+        (text.into_owned(), had_errors)
+    };
+
+    if offset > 0 {
+        if let Some(newline) = text.find('\n') {
+            text.drain(..=newline);
+        }
+    }
+
+    Ok(DecodedText {
+        text,
+        encoding,
+        had_errors,
+    })
+}
+
+pub fn detect_line_ending(text: &str) -> LineEnding {
+    let (mut crlf, mut lf, mut cr) = (0usize, 0usize, 0usize);
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() == Some(&'\n') => {
+                chars.next();
+                crlf += 1;
+            }
+            '\r' => cr += 1,
+            '\n' => lf += 1,
+            _ => {}
+        }
+    }
+
+    match (crlf > 0, lf > 0, cr > 0) {
+        (false, false, false) => LineEnding::None,
+        (true, false, false) => LineEnding::Crlf,
+        (false, true, false) => LineEnding::Lf,
+        (false, false, true) => LineEnding::Cr,
+        _ => LineEnding::Mixed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cp949(text: &str) -> Vec<u8> {
+        EUC_KR.encode(text).0.into_owned()
+    }
+
+    #[test]
+    fn detects_bom_and_plain_utf8() {
+        assert_eq!(
+            detect_encoding(b"\xEF\xBB\xBFabc", 0, false).unwrap(),
+            (UTF_8, 3)
+        );
+        assert_eq!(
+            detect_encoding("한글 텍스트".as_bytes(), 0, false).unwrap(),
+            (UTF_8, 0)
+        );
+        assert!(detect_encoding(b"ab\0cd", 0, false).is_err());
+    }
+
+    #[test]
+    fn detects_utf8_read_from_the_middle_of_a_character() {
+        let data = "첫 줄\n둘째 줄\n셋째 줄".as_bytes();
+        // "첫"의 두 번째 바이트부터 읽은 경우
+        assert_eq!(detect_encoding(&data[1..], 1, false).unwrap().0, UTF_8);
+        // 줄바꿈 없이 중간에서 시작하고 끝도 잘린 경우
+        let line = "가나다라마바사".as_bytes();
+        assert_eq!(
+            detect_encoding(&line[2..line.len() - 1], 2, true)
+                .unwrap()
+                .0,
+            UTF_8
+        );
+    }
+
+    #[test]
+    fn detects_cp949_cut_at_either_end() {
+        let data = cp949("첫 줄입니다\n한글로 된 둘째 줄\n셋째 줄");
+        assert_eq!(detect_encoding(&data, 0, false).unwrap().0, EUC_KR);
+        assert_eq!(
+            detect_encoding(&data[..data.len() - 1], 0, true).unwrap().0,
+            EUC_KR
+        );
+        assert_eq!(
+            detect_encoding(&data[1..data.len() - 1], 1, true)
+                .unwrap()
+                .0,
+            EUC_KR
+        );
+
+        let line = cp949("가나다라마바사");
+        assert_eq!(
+            detect_encoding(&line[1..line.len() - 1], 1, true)
+                .unwrap()
+                .0,
+            EUC_KR
+        );
+    }
+
+    #[test]
+    fn falls_back_to_windows_1252() {
+        assert_eq!(
+            detect_encoding(b"caf\xE9 cr\xE8me", 0, false).unwrap().0,
+            WINDOWS_1252
+        );
+    }
+
+    #[test]
+    fn decode_text_drops_partial_first_line() {
+        let data = "첫 줄\n둘째 줄".as_bytes();
+        let decoded = decode_text(&data[1..], 1, false, None).unwrap();
+        assert_eq!(decoded.text, "둘째 줄");
+        assert_eq!(decoded.encoding, UTF_8);
+    }
+
+    #[test]
+    fn decode_text_drops_cut_cp949_lead_byte() {
+        let data = cp949("한글 미리보기");
+        let cut = &data[..data.len() - 1];
+
+        let decoded = decode_text(cut, 0, true, Some("euc-kr")).unwrap();
+        assert_eq!(decoded.text, "한글 미리보");
+        assert!(!decoded.had_errors);
+
+        let decoded = decode_text(cut, 0, true, None).unwrap();
+        assert_eq!(decoded.encoding, EUC_KR);
+        assert_eq!(decoded.text, "한글 미리보");
+        assert!(!decoded.had_errors);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::listing::Matcher;
+use crate::FileEntry;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchOptions {
+    pub pattern: Option<String>,
+    pub regex: bool,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub modified_after: Option<String>,
+    pub modified_before: Option<String>,
+    pub max_depth: Option<usize>,
+    pub files_only: bool,
+    pub show_hidden: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub search_id: String,
+    pub entries: Vec<FileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSummary {
+    pub search_id: String,
+    pub matched: usize,
+    pub scanned: usize,
+    // 읽지 못해 건너뛴 하위 폴더 수
+    pub unreadable: usize,
+    pub cancelled: bool,
+}
+
+/// 원격 서버마다 수정 시각 형식이 달라 RFC 3339와 "YYYY-MM-DD[ HH:MM]"을 모두 받는다
+pub fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.naive_utc());
+    }
+
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
+
+fn parse_bound(value: Option<&str>) -> Result<Option<NaiveDateTime>, String> {
+    match value.map(|v| v.trim()) {
+        None | Some("") => Ok(None),
+        Some(value) => parse_time(value)
+            .map(Some)
+            .ok_or_else(|| format!("날짜 형식이 올바르지 않습니다: {}", value)),
+    }
+}
+
+pub struct SearchFilter {
+    matcher: Matcher,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    after: Option<NaiveDateTime>,
+    before: Option<NaiveDateTime>,
+    files_only: bool,
+}
+
+impl SearchFilter {
+    pub fn new(options: &SearchOptions) -> Result<Self, String> {
+        Ok(Self {
+            matcher: Matcher::new(options.pattern.as_deref(), options.regex)?,
+            min_size: options.min_size,
+            max_size: options.max_size,
+            after: parse_bound(options.modified_after.as_deref())?,
+            before: parse_bound(options.modified_before.as_deref())?,
+            files_only: options.files_only,
+        })
+    }
+
+    pub fn matches(&self, entry: &FileEntry) -> bool {
+        if !self.matcher.matches(&entry.name) {
+            return false;
+        }
+
+        // 크기 조건이 있으면 폴더는 결과에서 제외한다
+        let size_filtered = self.min_size.is_some() || self.max_size.is_some();
+        if entry.is_directory && (self.files_only || size_filtered) {
+            return false;
+        }
+
+        if self.min_size.is_some_and(|min| entry.size < min)
+            || self.max_size.is_some_and(|max| entry.size > max)
+        {
+            return false;
+        }
+
+        if self.after.is_some() || self.before.is_some() {
+            // 수정 시각을 알 수 없는 항목(FTP NLST 등)은 날짜 조건을 만족하지 않는다
+            let Some(modified) = parse_time(&entry.modified) else {
+                return false;
+            };
+            if self.after.is_some_and(|after| modified < after)
+                || self.before.is_some_and(|before| modified > before)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_directory: bool, size: u64, modified: &str) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            path: format!("/data/{}", name),
+            is_directory,
+            size,
+            modified: modified.to_string(),
+            permissions: None,
+        }
+    }
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn parses_supported_time_formats() {
+        assert_eq!(
+            parse_time("2024-03-05T10:20:30+09:00"),
+            Some(time("2024-03-05 01:20:30"))
+        );
+        assert_eq!(
+            parse_time(" 2024-03-05 10:20:30 "),
+            Some(time("2024-03-05 10:20:30"))
+        );
+        assert_eq!(
+            parse_time("2024-03-05 10:20"),
+            Some(time("2024-03-05 10:20:00"))
+        );
+        assert_eq!(parse_time("2024-03-05"), Some(time("2024-03-05 00:00:00")));
+        assert_eq!(parse_time("Mar 5 10:20"), None);
+        assert_eq!(parse_time(""), None);
+    }
+
+    #[test]
+    fn filters_by_size() {
+        let filter = SearchFilter::new(&SearchOptions {
+            min_size: Some(100),
+            max_size: Some(1000),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(filter.matches(&entry("a.log", false, 100, "")));
+        assert!(filter.matches(&entry("b.log", false, 1000, "")));
+        assert!(!filter.matches(&entry("c.log", false, 99, "")));
+        assert!(!filter.matches(&entry("d.log", false, 1001, "")));
+        // 크기 조건이 있으면 폴더는 제외한다
+        assert!(!filter.matches(&entry("logs", true, 500, "")));
+    }
+
+    #[test]
+    fn filters_by_modified_time() {
+        let filter = SearchFilter::new(&SearchOptions {
+            modified_after: Some("2024-01-01".to_string()),
+            modified_before: Some("2024-06-30 23:59".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(filter.matches(&entry("a", false, 0, "2024-01-01 00:00")));
+        assert!(filter.matches(&entry("b", false, 0, "2024-03-05T10:20:30Z")));
+        assert!(!filter.matches(&entry("c", false, 0, "2023-12-31 23:59")));
+        assert!(!filter.matches(&entry("d", false, 0, "2024-07-01 00:00")));
+        // 수정 시각을 알 수 없으면 날짜 조건을 만족하지 않는다
+        assert!(!filter.matches(&entry("e", false, 0, "")));
+
+        assert!(SearchFilter::new(&SearchOptions {
+            modified_after: Some("yesterday".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn filters_by_name() {
+        let glob = SearchFilter::new(&SearchOptions {
+            pattern: Some("*.LOG".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(glob.matches(&entry("app.log", false, 0, "")));
+        assert!(!glob.matches(&entry("app.txt", false, 0, "")));
+
+        let regex = SearchFilter::new(&SearchOptions {
+            pattern: Some(r"^app-\d+\.log$".to_string()),
+            regex: true,
+            files_only: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(regex.matches(&entry("app-12.log", false, 0, "")));
+        assert!(!regex.matches(&entry("app-x.log", false, 0, "")));
+        assert!(!regex.matches(&entry("app-12.log", true, 0, "")));
+
+        assert!(SearchFilter::new(&SearchOptions {
+            pattern: Some("(".to_string()),
+            regex: true,
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
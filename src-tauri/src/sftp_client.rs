@@ -1,78 +1,166 @@
 use crate::FileEntry;
-use ssh2::{Session, Sftp};
+use ssh2::{Channel, Session, Sftp};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
 use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 pub struct SftpConnection {
     session: Session,
     sftp: Sftp,
+    // 연결과 함께 드롭되면서 ssh -W 프로세스를 종료한다
+    _proxy: Option<JumpProxy>,
+    shells: HashMap<String, Channel>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExecStream {
+    Stdout,
+    Stderr,
+}
+
+pub struct ShellRead {
+    pub shell_id: String,
+    pub data: Vec<u8>,
+    pub closed: bool,
+    pub exit_status: Option<i32>,
+}
+
+pub struct ExecOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    // stdout이 한도를 넘어 중간에 멈췄으면 true이고, 이때 exit_status는 -1이다
+    pub truncated: bool,
+    pub exit_status: i32,
+}
+
+// 오류 메시지 확인용이라 stderr는 앞부분만 보관한다
+const EXEC_STDERR_LIMIT: usize = 64 * 1024;
+// 중계 소켓에 다른 프로세스가 먼저 붙었을 때 다시 기다리는 최대 횟수
+const RELAY_ACCEPT_ATTEMPTS: usize = 16;
+// poll_exec 한 번에 읽는 최대 횟수. 출력이 끊임없이 나와도 연결을 곧 놓아준다
+const EXEC_POLL_READS: usize = 64;
+
+pub enum ExecPoll {
+    // 읽을 출력이 없었다
+    Idle,
+    Output,
+    Finished(i32),
+}
+
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
 }
 
 impl SftpConnection {
-    pub fn connect(host: &str, port: u16, username: &str, password: &str) -> Result<Self, String> {
-        let addr = format!("{}:{}", host, port);
-        let tcp = TcpStream::connect(&addr).map_err(|e| format!("SFTP 연결 실패: {}", e))?;
-
-        let mut session = Session::new().map_err(|e| format!("세션 생성 실패: {}", e))?;
-        session.set_tcp_stream(tcp);
-        session
-            .handshake()
-            .map_err(|e| format!("핸드셰이크 실패: {}", e))?;
-
-        session
-            .userauth_password(username, password)
-            .map_err(|e| format!("SFTP 인증 실패: {}", e))?;
-
-        if !session.authenticated() {
-            return Err("인증 실패".to_string());
-        }
+    pub fn connect(
+        host: &str,
+        port: u16,
+        username: &str,
+        password: &str,
+        identity_file: Option<&str>,
+        proxy_jump: Option<&str>,
+        timeout: Duration,
+    ) -> Result<Self, String> {
+        let (tcp, proxy) = match proxy_jump {
+            Some(jump) => {
+                let (tcp, proxy) = connect_via_jump(jump, host, port, timeout)?;
+                (tcp, Some(proxy))
+            }
+            None => {
+                let addr = format!("{}:{}", host, port)
+                    .to_socket_addrs()
+                    .map_err(|e| format!("SFTP 주소 확인 실패: {}", e))?
+                    .next()
+                    .ok_or("SFTP 주소를 찾을 수 없습니다")?;
+                let tcp = TcpStream::connect_timeout(&addr, timeout)
+                    .map_err(|e| format!("SFTP 연결 실패: {}", e))?;
+                (tcp, None)
+            }
+        };
 
-        let sftp = session
-            .sftp()
-            .map_err(|e| format!("SFTP 서브시스템 시작 실패: {}", e))?;
+        let (session, sftp) = match open_session(tcp, username, password, identity_file, timeout) {
+            Ok(opened) => opened,
+            // ProxyJump가 실패하면 ssh가 stderr에 남긴 이유를 함께 보여준다
+            Err(e) => {
+                return Err(match proxy {
+                    Some(proxy) => proxy.fail(e),
+                    None => e,
+                })
+            }
+        };
 
-        Ok(Self { session, sftp })
+        Ok(Self {
+            session,
+            sftp,
+            _proxy: proxy,
+            shells: HashMap::new(),
+        })
     }
 
-    pub fn list_files(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+    /// 디렉토리 항목을 읽는 대로 하나씩 넘긴다
+    pub fn list_files_each(
+        &mut self,
+        path: &str,
+        show_hidden: bool,
+        on_entry: &mut dyn FnMut(FileEntry),
+    ) -> Result<(), String> {
         let path = if path.is_empty() { "/" } else { path };
         let remote_path = Path::new(path);
 
-        let dir = self
+        let mut dir = self
             .sftp
-            .readdir(remote_path)
+            .opendir(remote_path)
             .map_err(|e| format!("디렉토리 읽기 실패: {}", e))?;
 
-        let mut entries: Vec<FileEntry> = dir
-            .into_iter()
-            .filter_map(|(path_buf, stat)| {
-                let name = path_buf.file_name()?.to_string_lossy().to_string();
+        loop {
+            let (file_name, stat) = match dir.readdir() {
+                Ok(entry) => entry,
+                // LIBSSH2_ERROR_FILE: 더 읽을 항목이 없다
+                Err(e) if e.code() == ssh2::ErrorCode::Session(-16) => break,
+                Err(e) => return Err(format!("디렉토리 읽기 실패: {}", e)),
+            };
 
-                if name == "." || name == ".." {
-                    return None;
-                }
+            let Some(name) = file_name
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+            else {
+                continue;
+            };
 
-                let modified = stat
-                    .mtime
-                    .map(|t| {
-                        chrono::DateTime::from_timestamp(t as i64, 0)
-                            .map(|dt| dt.to_rfc3339())
-                            .unwrap_or_default()
-                    })
-                    .unwrap_or_default();
-
-                Some(FileEntry {
-                    name,
-                    path: path_buf.to_string_lossy().to_string(),
-                    is_directory: stat.is_dir(),
-                    size: stat.size.unwrap_or(0),
-                    modified,
-                    permissions: stat.perm.map(|p| format!("{:o}", p)),
+            if name == "." || name == ".." || (!show_hidden && name.starts_with('.')) {
+                continue;
+            }
+
+            let modified = stat
+                .mtime
+                .map(|t| {
+                    chrono::DateTime::from_timestamp(t as i64, 0)
+                        .map(|dt| dt.to_rfc3339())
+                        .unwrap_or_default()
                 })
-            })
-            .collect();
+                .unwrap_or_default();
+
+            on_entry(FileEntry {
+                name,
+                path: remote_path.join(&file_name).to_string_lossy().to_string(),
+                is_directory: stat.is_dir(),
+                size: stat.size.unwrap_or(0),
+                modified,
+                permissions: stat.perm.map(|p| format!("{:o}", p)),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn list_files(&mut self, path: &str, show_hidden: bool) -> Result<Vec<FileEntry>, String> {
+        let mut entries = Vec::new();
+        self.list_files_each(path, show_hidden, &mut |entry| entries.push(entry))?;
 
         entries.sort_by(|a, b| {
             if a.is_directory == b.is_directory {
@@ -87,6 +175,28 @@ impl SftpConnection {
         Ok(entries)
     }
 
+    pub fn stat(&mut self, remote_path: &str) -> Result<FileEntry, String> {
+        let stat = self
+            .sftp
+            .stat(Path::new(remote_path))
+            .map_err(|e| format!("파일 정보 조회 실패: {}", e))?;
+
+        let modified = stat
+            .mtime
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0))
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+
+        Ok(FileEntry {
+            name: remote_path.rsplit('/').next().unwrap_or(remote_path).to_string(),
+            path: remote_path.to_string(),
+            is_directory: stat.is_dir(),
+            size: stat.size.unwrap_or(0),
+            modified,
+            permissions: stat.perm.map(|p| format!("{:o}", p)),
+        })
+    }
+
     pub fn download(&mut self, remote_path: &str, local_path: &str) -> Result<(), String> {
         let mut remote_file = self
             .sftp
@@ -143,6 +253,44 @@ impl SftpConnection {
         Ok(buffer)
     }
 
+    /// 파일 전체를 메모리에 올리지 않고 읽기 스트림을 넘긴다
+    pub fn read_with(
+        &mut self,
+        remote_path: &str,
+        read: &mut dyn FnMut(&mut dyn Read) -> std::io::Result<()>,
+    ) -> Result<(), String> {
+        let mut remote_file = self
+            .sftp
+            .open(Path::new(remote_path))
+            .map_err(|e| format!("원격 파일 열기 실패: {}", e))?;
+
+        read(&mut remote_file).map_err(|e| format!("파일 읽기 실패: {}", e))
+    }
+
+    pub fn read_range(
+        &mut self,
+        remote_path: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, String> {
+        let mut remote_file = self
+            .sftp
+            .open(Path::new(remote_path))
+            .map_err(|e| format!("원격 파일 열기 실패: {}", e))?;
+
+        remote_file
+            .seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("파일 위치 이동 실패: {}", e))?;
+
+        let mut buffer = Vec::new();
+        remote_file
+            .take(length)
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("파일 읽기 실패: {}", e))?;
+
+        Ok(buffer)
+    }
+
     pub fn delete(&mut self, remote_path: &str, is_directory: bool) -> Result<(), String> {
         let path = Path::new(remote_path);
         if is_directory {
@@ -156,4 +304,539 @@ impl SftpConnection {
         }
         Ok(())
     }
+
+    pub fn create_dir(&mut self, remote_path: &str) -> Result<(), String> {
+        self.sftp
+            .mkdir(Path::new(remote_path), 0o755)
+            .map_err(|e| format!("폴더 생성 실패: {}", e))
+    }
+
+    /// 명령을 실행하고 출력을 모은다. stdout과 stderr를 함께 읽으므로 한쪽이 많아도 멈추지 않는다.
+    /// stdout이 max_output을 넘으면 읽기를 멈추고, timeout이 지나면 채널을 닫고 실패한다.
+    pub fn exec(
+        &mut self,
+        command: &str,
+        max_output: usize,
+        timeout: Option<Duration>,
+    ) -> Result<ExecOutput, String> {
+        let mut channel = self
+            .session
+            .channel_session()
+            .map_err(|e| format!("SSH 채널 열기 실패: {}", e))?;
+        channel
+            .exec(command)
+            .map_err(|e| format!("원격 명령 실행 실패: {}", e))?;
+
+        let full = AtomicBool::new(false);
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        self.session.set_blocking(false);
+        let finished = pump_exec(&mut channel, &full, deadline, &mut |stream, data| {
+            let (buffer, limit) = match stream {
+                ExecStream::Stdout => (&mut stdout, max_output),
+                ExecStream::Stderr => (&mut stderr, EXEC_STDERR_LIMIT),
+            };
+            let room = limit.saturating_sub(buffer.len());
+            buffer.extend_from_slice(&data[..data.len().min(room)]);
+            if matches!(stream, ExecStream::Stdout) && data.len() > room {
+                full.store(true, Ordering::Relaxed);
+            }
+        });
+        self.session.set_blocking(true);
+
+        match finished {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = channel.close();
+                return Ok(ExecOutput {
+                    stdout,
+                    stderr,
+                    truncated: true,
+                    exit_status: -1,
+                });
+            }
+            Err(e) => {
+                let _ = channel.close();
+                return Err(e);
+            }
+        }
+
+        channel
+            .wait_close()
+            .map_err(|e| format!("SSH 채널 종료 실패: {}", e))?;
+        let exit_status = channel
+            .exit_status()
+            .map_err(|e| format!("종료 코드 확인 실패: {}", e))?;
+
+        Ok(ExecOutput {
+            stdout,
+            stderr,
+            truncated: false,
+            exit_status,
+        })
+    }
+
+    /// 명령을 실행할 채널을 연다. 출력은 poll_exec으로 조금씩 읽는다.
+    pub fn start_exec(&mut self, command: &str) -> Result<Channel, String> {
+        let mut channel = self
+            .session
+            .channel_session()
+            .map_err(|e| format!("SSH 채널 열기 실패: {}", e))?;
+        channel
+            .exec(command)
+            .map_err(|e| format!("원격 명령 실행 실패: {}", e))?;
+
+        Ok(channel)
+    }
+
+    /// 지금 읽을 수 있는 출력만 넘기고 바로 돌아온다. 연결을 오래 잡지 않도록 실행 중인 명령을 기다리지 않는다.
+    pub fn poll_exec(
+        &mut self,
+        channel: &mut Channel,
+        on_output: &mut dyn FnMut(ExecStream, &[u8]),
+    ) -> Result<ExecPoll, String> {
+        self.session.set_blocking(false);
+        let mut read_any = false;
+        let mut result = Ok(());
+        for _ in 0..EXEC_POLL_READS {
+            match read_available(channel, on_output) {
+                Ok(true) => read_any = true,
+                Ok(false) => break,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        let finished = result.is_ok() && !read_any && channel.eof();
+        self.session.set_blocking(true);
+        result?;
+
+        if !finished {
+            return Ok(if read_any {
+                ExecPoll::Output
+            } else {
+                ExecPoll::Idle
+            });
+        }
+
+        channel
+            .wait_close()
+            .map_err(|e| format!("SSH 채널 종료 실패: {}", e))?;
+        channel
+            .exit_status()
+            .map(ExecPoll::Finished)
+            .map_err(|e| format!("종료 코드 확인 실패: {}", e))
+    }
+
+    pub fn close_exec(&mut self, channel: &mut Channel) {
+        let _ = channel.close();
+    }
+
+    pub fn open_shell(&mut self, shell_id: &str, cols: u32, rows: u32) -> Result<(), String> {
+        let mut channel = self
+            .session
+            .channel_session()
+            .map_err(|e| format!("SSH 채널 열기 실패: {}", e))?;
+        channel
+            .request_pty("xterm-256color", None, Some((cols, rows, 0, 0)))
+            .map_err(|e| format!("터미널 할당 실패: {}", e))?;
+        channel
+            .shell()
+            .map_err(|e| format!("셸 시작 실패: {}", e))?;
+
+        self.shells.insert(shell_id.to_string(), channel);
+        Ok(())
+    }
+
+    fn shell(&mut self, shell_id: &str) -> Result<&mut Channel, String> {
+        self.shells
+            .get_mut(shell_id)
+            .ok_or_else(|| "터미널 세션을 찾을 수 없습니다".to_string())
+    }
+
+    pub fn write_shell(&mut self, shell_id: &str, data: &[u8]) -> Result<(), String> {
+        let channel = self.shell(shell_id)?;
+        channel
+            .write_all(data)
+            .and_then(|_| channel.flush())
+            .map_err(|e| format!("터미널 입력 실패: {}", e))
+    }
+
+    pub fn resize_shell(&mut self, shell_id: &str, cols: u32, rows: u32) -> Result<(), String> {
+        self.shell(shell_id)?
+            .request_pty_size(cols, rows, None, None)
+            .map_err(|e| format!("터미널 크기 변경 실패: {}", e))
+    }
+
+    pub fn close_shell(&mut self, shell_id: &str) -> Result<(), String> {
+        let mut channel = self
+            .shells
+            .remove(shell_id)
+            .ok_or_else(|| "터미널 세션을 찾을 수 없습니다".to_string())?;
+        channel
+            .close()
+            .map_err(|e| format!("터미널 종료 실패: {}", e))
+    }
+
+    pub fn has_shells(&self) -> bool {
+        !self.shells.is_empty()
+    }
+
+    /// 열린 셸에서 지금 읽을 수 있는 출력만 가져온다. 종료된 셸은 목록에서 뺀다.
+    pub fn poll_shells(&mut self) -> Vec<ShellRead> {
+        let mut reads = Vec::new();
+        let mut buffer = [0u8; 8192];
+
+        self.session.set_blocking(false);
+
+        for (shell_id, channel) in self.shells.iter_mut() {
+            let mut data = Vec::new();
+            let mut failed = false;
+
+            loop {
+                match channel.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(n) => data.extend_from_slice(&buffer[..n]),
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(_) => {
+                        failed = true;
+                        break;
+                    }
+                }
+            }
+
+            let closed = failed || channel.eof();
+            if !data.is_empty() || closed {
+                reads.push(ShellRead {
+                    shell_id: shell_id.clone(),
+                    data,
+                    closed,
+                    exit_status: if closed { channel.exit_status().ok() } else { None },
+                });
+            }
+        }
+
+        self.session.set_blocking(true);
+
+        for read in reads.iter().filter(|r| r.closed) {
+            if let Some(mut channel) = self.shells.remove(&read.shell_id) {
+                let _ = channel.close();
+            }
+        }
+
+        reads
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        self.sftp
+            .rename(Path::new(from), Path::new(to), None)
+            .map_err(|e| format!("이름 변경 실패: {}", e))
+    }
+}
+
+/// 두 스트림에서 한 번씩 읽을 수 있는 만큼 읽는다. 읽은 것이 있으면 true다.
+fn read_available(
+    channel: &mut Channel,
+    on_output: &mut dyn FnMut(ExecStream, &[u8]),
+) -> Result<bool, String> {
+    let mut buffer = [0u8; 8192];
+    let mut read_any = false;
+
+    for stream in [ExecStream::Stdout, ExecStream::Stderr] {
+        let read = match stream {
+            ExecStream::Stdout => channel.read(&mut buffer),
+            ExecStream::Stderr => channel.stderr().read(&mut buffer),
+        };
+        match read {
+            Ok(0) => {}
+            Ok(n) => {
+                on_output(stream, &buffer[..n]);
+                read_any = true;
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(format!("명령 출력 읽기 실패: {}", e)),
+        }
+    }
+
+    Ok(read_any)
+}
+
+/// 채널이 끝날 때까지 두 스트림을 번갈아 읽는다. 중단되면 false, 시간이 초과되면 오류를 돌려준다.
+fn pump_exec(
+    channel: &mut Channel,
+    cancelled: &AtomicBool,
+    deadline: Option<Instant>,
+    on_output: &mut dyn FnMut(ExecStream, &[u8]),
+) -> Result<bool, String> {
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err("원격 명령 실행 시간이 초과되었습니다".to_string());
+        }
+
+        if !read_available(channel, on_output)? {
+            if channel.eof() {
+                return Ok(true);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+/// ProxyJump용 ssh -W 프로세스. 연결이 끝나면 함께 종료한다.
+struct JumpProxy {
+    child: Child,
+    stderr: Option<std::thread::JoinHandle<Vec<u8>>>,
+}
+
+impl JumpProxy {
+    /// 프로세스를 멈추고 stderr에 남은 내용을 오류 메시지에 붙인다
+    fn fail(mut self, message: String) -> String {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let log = self
+            .stderr
+            .take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+        let log = String::from_utf8_lossy(&log);
+        let log = log.trim();
+        if log.is_empty() {
+            message
+        } else {
+            format!("{} (ProxyJump: {})", message, log)
+        }
+    }
+}
+
+impl Drop for JumpProxy {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn open_session(
+    tcp: TcpStream,
+    username: &str,
+    password: &str,
+    identity_file: Option<&str>,
+    timeout: Duration,
+) -> Result<(Session, Sftp), String> {
+    let mut session = Session::new().map_err(|e| format!("세션 생성 실패: {}", e))?;
+    session.set_timeout(timeout.as_millis().min(u32::MAX as u128) as u32);
+    session.set_tcp_stream(tcp);
+    session
+        .handshake()
+        .map_err(|e| format!("핸드셰이크 실패: {}", e))?;
+
+    authenticate(&session, username, password, identity_file)?;
+
+    if !session.authenticated() {
+        return Err("인증 실패".to_string());
+    }
+
+    let sftp = session
+        .sftp()
+        .map_err(|e| format!("SFTP 서브시스템 시작 실패: {}", e))?;
+
+    // 연결 타임아웃은 접속 과정에만 적용한다. 큰 파일 전송이나 느린 디렉토리 읽기가 중간에 끊기지 않게 한다.
+    session.set_timeout(0);
+
+    Ok((session, sftp))
+}
+
+fn authenticate(
+    session: &Session,
+    username: &str,
+    password: &str,
+    identity_file: Option<&str>,
+) -> Result<(), String> {
+    if let Some(key_path) = identity_file {
+        let passphrase = Some(password).filter(|p| !p.is_empty());
+        let result =
+            session.userauth_pubkey_file(username, None, Path::new(key_path), passphrase);
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if password.is_empty() => return Err(format!("SFTP 키 인증 실패: {}", e)),
+            Err(_) => {}
+        }
+    }
+
+    if password.is_empty() {
+        return session
+            .userauth_agent(username)
+            .map_err(|e| format!("SSH 에이전트 인증 실패: {}", e));
+    }
+
+    session
+        .userauth_password(username, password)
+        .map_err(|e| format!("SFTP 인증 실패: {}", e))
+}
+
+/// 마지막 단계에 ssh -W로 붙고, 그 앞의 단계들은 -J로 넘긴다
+fn jump_command_args(
+    jump: &str,
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<Vec<String>, String> {
+    let hops = crate::ssh_config::parse_proxy_jump(jump)?;
+    let (last, earlier) = hops.split_last().ok_or("ProxyJump 값이 비어 있습니다")?;
+
+    // 점프 호스트에 붙는 과정에도 연결 타임아웃을 적용한다
+    let mut args = vec![
+        "-o".to_string(),
+        format!("ConnectTimeout={}", timeout.as_secs().max(1)),
+    ];
+    if !earlier.is_empty() {
+        let specs: Vec<String> = earlier.iter().map(|hop| hop.to_spec()).collect();
+        args.push("-J".to_string());
+        args.push(specs.join(","));
+    }
+    if let Some(jump_port) = last.port {
+        args.push("-p".to_string());
+        args.push(jump_port.to_string());
+    }
+    if let Some(user) = &last.user {
+        args.push("-l".to_string());
+        args.push(user.clone());
+    }
+
+    let target = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    args.push("-W".to_string());
+    args.push(target);
+    args.push("--".to_string());
+    args.push(last.host.clone());
+
+    Ok(args)
+}
+
+fn connect_via_jump(
+    jump: &str,
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<(TcpStream, JumpProxy), String> {
+    let mut child = Command::new("ssh")
+        .args(jump_command_args(jump, host, port, timeout)?)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("ProxyJump 실행 실패: {}", e))?;
+
+    let child_stdin = child.stdin.take();
+    let child_stdout = child.stdout.take();
+    let child_stderr = child.stderr.take();
+    let mut proxy = JumpProxy {
+        child,
+        stderr: None,
+    };
+    let mut child_stdin = child_stdin.ok_or("ProxyJump 입력 연결 실패")?;
+    let mut child_stdout = child_stdout.ok_or("ProxyJump 출력 연결 실패")?;
+    let mut child_stderr = child_stderr.ok_or("ProxyJump 오류 출력 연결 실패")?;
+
+    // 앞부분만 보관하고 나머지는 버려서 ssh가 stderr 쓰기에서 멈추지 않게 한다
+    proxy.stderr = Some(std::thread::spawn(move || {
+        let mut log = Vec::new();
+        let _ = (&mut child_stderr)
+            .take(EXEC_STDERR_LIMIT as u64)
+            .read_to_end(&mut log);
+        let _ = std::io::copy(&mut child_stderr, &mut std::io::sink());
+        log
+    }));
+
+    // libssh2는 소켓이 필요하므로 ssh -W 의 표준 입출력을 로컬 소켓으로 중계한다
+    let listener =
+        TcpListener::bind("127.0.0.1:0").map_err(|e| format!("로컬 중계 소켓 생성 실패: {}", e))?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| format!("로컬 중계 소켓 생성 실패: {}", e))?;
+    let tcp = TcpStream::connect(addr).map_err(|e| format!("로컬 중계 연결 실패: {}", e))?;
+    let local = tcp
+        .local_addr()
+        .map_err(|e| format!("로컬 중계 연결 실패: {}", e))?;
+
+    // 같은 포트에 다른 로컬 프로세스가 먼저 붙을 수 있으므로 우리 소켓인지 확인한다
+    let mut relay = None;
+    for _ in 0..RELAY_ACCEPT_ATTEMPTS {
+        let (stream, peer) = listener
+            .accept()
+            .map_err(|e| format!("로컬 중계 연결 실패: {}", e))?;
+        if peer == local {
+            relay = Some(stream);
+            break;
+        }
+    }
+    let relay = relay.ok_or("로컬 중계 연결을 확인할 수 없습니다")?;
+
+    let mut relay_reader = relay
+        .try_clone()
+        .map_err(|e| format!("로컬 중계 연결 실패: {}", e))?;
+    let mut relay_writer = relay;
+
+    std::thread::spawn(move || {
+        let _ = std::io::copy(&mut relay_reader, &mut child_stdin);
+    });
+    std::thread::spawn(move || {
+        let _ = std::io::copy(&mut child_stdout, &mut relay_writer);
+        let _ = relay_writer.shutdown(std::net::Shutdown::Both);
+    });
+
+    Ok((tcp, proxy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jump_args_for_single_and_multiple_hops() {
+        assert_eq!(
+            jump_command_args("admin@bastion:2200", "db", 22, Duration::from_secs(10)).unwrap(),
+            [
+                "-o",
+                "ConnectTimeout=10",
+                "-p",
+                "2200",
+                "-l",
+                "admin",
+                "-W",
+                "db:22",
+                "--",
+                "bastion"
+            ]
+        );
+        assert_eq!(
+            jump_command_args(
+                "one,u@two:2201,three",
+                "::1",
+                2222,
+                Duration::from_millis(500)
+            )
+            .unwrap(),
+            [
+                "-o",
+                "ConnectTimeout=1",
+                "-J",
+                "one,u@two:2201",
+                "-W",
+                "[::1]:2222",
+                "--",
+                "three"
+            ]
+        );
+        assert!(jump_command_args("", "db", 22, Duration::from_secs(10)).is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use crate::FileEntry;
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -68,20 +68,24 @@ impl SmbConnection {
         }
     }
 
-    pub fn list_files(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+    /// 디렉토리 항목을 읽는 대로 하나씩 넘긴다
+    pub fn list_files_each(
+        &mut self,
+        path: &str,
+        show_hidden: bool,
+        on_entry: &mut dyn FnMut(FileEntry),
+    ) -> Result<(), String> {
         let local_path = self.get_local_path(path);
         
         let entries = fs::read_dir(&local_path)
             .map_err(|e| format!("디렉토리 읽기 실패: {}", e))?;
 
-        let mut files: Vec<FileEntry> = Vec::new();
-
         for entry in entries {
             let entry = entry.map_err(|e| format!("항목 읽기 실패: {}", e))?;
             let metadata = entry.metadata().map_err(|e| format!("메타데이터 읽기 실패: {}", e))?;
             let name = entry.file_name().to_string_lossy().to_string();
             
-            if name.starts_with('.') {
+            if !show_hidden && name.starts_with('.') {
                 continue;
             }
 
@@ -102,7 +106,7 @@ impl SmbConnection {
                 })
                 .unwrap_or_default();
 
-            files.push(FileEntry {
+            on_entry(FileEntry {
                 name,
                 path: file_path,
                 is_directory: metadata.is_dir(),
@@ -112,6 +116,13 @@ impl SmbConnection {
             });
         }
 
+        Ok(())
+    }
+
+    pub fn list_files(&mut self, path: &str, show_hidden: bool) -> Result<Vec<FileEntry>, String> {
+        let mut files = Vec::new();
+        self.list_files_each(path, show_hidden, &mut |entry| files.push(entry))?;
+
         files.sort_by(|a, b| {
             if a.is_directory == b.is_directory {
                 a.name.to_lowercase().cmp(&b.name.to_lowercase())
@@ -125,6 +136,28 @@ impl SmbConnection {
         Ok(files)
     }
 
+    pub fn stat(&mut self, remote_path: &str) -> Result<FileEntry, String> {
+        let metadata = fs::metadata(self.get_local_path(remote_path))
+            .map_err(|e| format!("파일 정보 조회 실패: {}", e))?;
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .and_then(|d| chrono::DateTime::from_timestamp(d.as_secs() as i64, 0))
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+
+        Ok(FileEntry {
+            name: remote_path.rsplit('/').next().unwrap_or(remote_path).to_string(),
+            path: remote_path.to_string(),
+            is_directory: metadata.is_dir(),
+            size: metadata.len(),
+            modified,
+            permissions: None,
+        })
+    }
+
     pub fn download(&mut self, remote_path: &str, local_path: &str) -> Result<(), String> {
         let src = self.get_local_path(remote_path);
         fs::copy(&src, local_path)
@@ -151,6 +184,38 @@ impl SmbConnection {
         Ok(buffer)
     }
 
+    pub fn read_with(
+        &mut self,
+        remote_path: &str,
+        read: &mut dyn FnMut(&mut dyn Read) -> std::io::Result<()>,
+    ) -> Result<(), String> {
+        let mut file = File::open(self.get_local_path(remote_path))
+            .map_err(|e| format!("파일 열기 실패: {}", e))?;
+
+        read(&mut file).map_err(|e| format!("파일 읽기 실패: {}", e))
+    }
+
+    pub fn read_range(
+        &mut self,
+        remote_path: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, String> {
+        let path = self.get_local_path(remote_path);
+        let mut file = File::open(&path)
+            .map_err(|e| format!("파일 열기 실패: {}", e))?;
+
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("파일 위치 이동 실패: {}", e))?;
+
+        let mut buffer = Vec::new();
+        file.take(length)
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("파일 읽기 실패: {}", e))?;
+
+        Ok(buffer)
+    }
+
     pub fn delete(&mut self, remote_path: &str, is_directory: bool) -> Result<(), String> {
         let path = self.get_local_path(remote_path);
         
@@ -164,6 +229,18 @@ impl SmbConnection {
         
         Ok(())
     }
+
+    pub fn create_dir(&mut self, remote_path: &str) -> Result<(), String> {
+        fs::create_dir(self.get_local_path(remote_path))
+            .map_err(|e| format!("폴더 생성 실패: {}", e))
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let src = self.get_local_path(from);
+        let dst = self.get_local_path(to);
+        fs::rename(&src, &dst)
+            .map_err(|e| format!("이름 변경 실패: {}", e))
+    }
 }
 
 impl Drop for SmbConnection {
@@ -0,0 +1,483 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ssh와 같이 Include가 너무 깊게 중첩되면 더 펼치지 않는다
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Default)]
+pub struct SshHost {
+    pub alias: String,
+    pub host_name: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub identity_file: Option<String>,
+    pub proxy_jump: Option<String>,
+}
+
+struct HostBlock {
+    patterns: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpHost {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+fn get_ssh_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("홈 디렉토리를 찾을 수 없습니다")?;
+    Ok(home_dir.join(".ssh"))
+}
+
+fn read_blocks() -> Result<Vec<HostBlock>, String> {
+    let ssh_dir = get_ssh_dir()?;
+    let path = ssh_dir.join("config");
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("SSH 설정 파일 읽기 실패: {}", e))?;
+
+    Ok(parse_blocks(&expand_includes(&content, &ssh_dir, 0)))
+}
+
+/// Include 인자를 파일 목록으로 바꾼다. 상대 경로는 ~/.ssh 기준이며 와일드카드를 쓸 수 있다.
+fn include_paths(value: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    for word in split_words(value) {
+        let expanded = PathBuf::from(expand_home(&word));
+        let pattern = if expanded.is_absolute() {
+            expanded
+        } else {
+            ssh_dir.join(expanded)
+        };
+
+        let Ok(matches) = glob::glob(&pattern.to_string_lossy()) else {
+            continue;
+        };
+        let mut matches: Vec<PathBuf> = matches.flatten().filter(|p| p.is_file()).collect();
+        matches.sort();
+        paths.extend(matches);
+    }
+
+    paths
+}
+
+/// Include 줄을 가리키는 파일의 내용으로 바꾼다. ssh와 같이 Include는 그 자리에 펼쳐지므로
+/// Host 블록 안에 있으면 그 블록의 조건을 따른다.
+fn expand_includes(content: &str, ssh_dir: &Path, depth: usize) -> String {
+    let mut expanded = String::new();
+
+    for line in content.lines() {
+        match split_line(line) {
+            Some((key, value)) if key == "include" && depth < MAX_INCLUDE_DEPTH => {
+                for path in include_paths(&value, ssh_dir) {
+                    if let Ok(included) = fs::read_to_string(&path) {
+                        expanded.push_str(&expand_includes(&included, ssh_dir, depth + 1));
+                        expanded.push('\n');
+                    }
+                }
+            }
+            _ => {
+                expanded.push_str(line);
+                expanded.push('\n');
+            }
+        }
+    }
+
+    expanded
+}
+
+/// ProxyJump의 한 단계([user@]host[:port] 또는 ssh://[user@]host[:port])를 나눈다.
+/// IPv6 주소는 [addr]:port 형태로 쓴다.
+pub fn parse_jump_host(value: &str) -> Option<JumpHost> {
+    let value = value.trim();
+    let value = value.strip_prefix("ssh://").unwrap_or(value);
+
+    let (user, address) = match value.rsplit_once('@') {
+        Some((user, address)) => (Some(user.to_string()).filter(|u| !u.is_empty()), address),
+        None => (None, value),
+    };
+
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        let port = match rest.strip_prefix(':') {
+            Some(port) => Some(port.parse().ok()?),
+            None if rest.is_empty() => None,
+            None => return None,
+        };
+        (host, port)
+    } else {
+        match address.split_once(':') {
+            Some((host, port)) => (host, Some(port.parse().ok()?)),
+            None => (address, None),
+        }
+    };
+
+    if host.is_empty() || host.starts_with('-') {
+        return None;
+    }
+
+    Some(JumpHost {
+        user,
+        host: host.to_string(),
+        port,
+    })
+}
+
+/// 쉼표로 구분된 여러 단계의 ProxyJump 값을 나눈다
+pub fn parse_proxy_jump(value: &str) -> Result<Vec<JumpHost>, String> {
+    let hops = value
+        .split(',')
+        .map(|hop| parse_jump_host(hop).ok_or_else(|| format!("잘못된 ProxyJump 값: {}", hop)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if hops.is_empty() {
+        return Err("ProxyJump 값이 비어 있습니다".to_string());
+    }
+
+    Ok(hops)
+}
+
+impl JumpHost {
+    /// ssh -J에 넘길 형태
+    pub fn to_spec(&self) -> String {
+        let mut spec = String::new();
+        if let Some(user) = &self.user {
+            spec.push_str(user);
+            spec.push('@');
+        }
+        if self.host.contains(':') {
+            spec.push_str(&format!("[{}]", self.host));
+        } else {
+            spec.push_str(&self.host);
+        }
+        if let Some(port) = self.port {
+            spec.push_str(&format!(":{}", port));
+        }
+        spec
+    }
+}
+
+fn split_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let key = line[..split_at].to_lowercase();
+    let value = line[split_at..]
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+        .trim();
+
+    if value.is_empty() {
+        return None;
+    }
+
+    Some((key, value.to_string()))
+}
+
+fn split_words(value: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in value.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn parse_blocks(content: &str) -> Vec<HostBlock> {
+    let mut blocks = Vec::new();
+    // 첫 Host 줄 이전의 옵션은 모든 호스트에 적용된다
+    let mut current = HostBlock {
+        patterns: vec!["*".to_string()],
+        options: Vec::new(),
+    };
+    let mut skipping = false;
+
+    for line in content.lines() {
+        let Some((key, value)) = split_line(line) else {
+            continue;
+        };
+
+        match key.as_str() {
+            "host" => {
+                blocks.push(current);
+                current = HostBlock {
+                    patterns: split_words(&value),
+                    options: Vec::new(),
+                };
+                skipping = false;
+            }
+            "match" => {
+                // Match 조건은 평가할 수 없으므로 다음 Host 블록까지 무시
+                skipping = true;
+            }
+            _ if skipping => {}
+            _ => current.options.push((key, value)),
+        }
+    }
+
+    blocks.push(current);
+    blocks
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn block_matches(block: &HostBlock, alias: &str) -> bool {
+    let mut matched = false;
+
+    for pattern in &block.patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if glob_match(negated, alias) {
+                return false;
+            }
+        } else if glob_match(pattern, alias) {
+            matched = true;
+        }
+    }
+
+    matched
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn resolve_in(blocks: &[HostBlock], alias: &str) -> SshHost {
+    let mut host = SshHost {
+        alias: alias.to_string(),
+        ..Default::default()
+    };
+
+    // ssh와 동일하게 처음 지정된 값이 우선한다
+    for block in blocks.iter().filter(|b| block_matches(b, alias)) {
+        for (key, value) in &block.options {
+            let value = value.trim_matches('"');
+            match key.as_str() {
+                "hostname" if host.host_name.is_none() => {
+                    host.host_name = Some(value.replace("%h", alias));
+                }
+                "port" if host.port.is_none() => host.port = value.parse().ok(),
+                "user" if host.user.is_none() => host.user = Some(value.to_string()),
+                "identityfile" if host.identity_file.is_none() => {
+                    host.identity_file = Some(expand_home(value));
+                }
+                "proxyjump" if host.proxy_jump.is_none() => {
+                    host.proxy_jump = Some(value.to_string()).filter(|v| v != "none");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    host
+}
+
+fn is_concrete_alias(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '!'])
+}
+
+pub fn load_hosts() -> Result<Vec<SshHost>, String> {
+    let blocks = read_blocks()?;
+    let mut aliases: Vec<&str> = Vec::new();
+
+    for block in &blocks {
+        for pattern in &block.patterns {
+            if is_concrete_alias(pattern) && !aliases.contains(&pattern.as_str()) {
+                aliases.push(pattern);
+            }
+        }
+    }
+
+    Ok(aliases
+        .into_iter()
+        .map(|alias| resolve_in(&blocks, alias))
+        .collect())
+}
+
+pub fn resolve(alias: &str) -> Result<Option<SshHost>, String> {
+    let blocks = read_blocks()?;
+
+    let declared = blocks
+        .iter()
+        .any(|b| b.patterns.iter().any(|p| is_concrete_alias(p) && p == alias));
+
+    if !declared {
+        return Ok(None);
+    }
+
+    Ok(Some(resolve_in(&blocks, alias)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+User global-user
+
+Host web web-*
+    HostName %h.example.com
+    Port 2222
+    IdentityFile ~/.ssh/web_key
+
+Host web-staging !web-prod
+    User staging
+
+Match host db
+    User ignored
+
+Host db
+    HostName=10.0.0.5
+    ProxyJump admin@bastion:2200,gateway
+
+Host *
+    User fallback
+    Port 22
+    ProxyJump none
+"#;
+
+    #[test]
+    fn first_value_wins_across_blocks() {
+        let blocks = parse_blocks(CONFIG);
+        let host = resolve_in(&blocks, "web");
+
+        assert_eq!(host.host_name.as_deref(), Some("web.example.com"));
+        assert_eq!(host.port, Some(2222));
+        // 첫 Host 줄 이전의 옵션이 가장 먼저 적용된다
+        assert_eq!(host.user.as_deref(), Some("global-user"));
+        assert!(host.identity_file.unwrap().ends_with(".ssh/web_key"));
+        assert_eq!(host.proxy_jump, None);
+    }
+
+    #[test]
+    fn wildcard_and_negated_patterns() {
+        let blocks = parse_blocks(CONFIG);
+
+        let staging = resolve_in(&blocks, "web-staging");
+        assert_eq!(
+            staging.host_name.as_deref(),
+            Some("web-staging.example.com")
+        );
+
+        let blocks = parse_blocks("Host web-* !web-prod\n    User deploy\n");
+        assert_eq!(
+            resolve_in(&blocks, "web-dev").user.as_deref(),
+            Some("deploy")
+        );
+        assert_eq!(resolve_in(&blocks, "web-prod").user, None);
+    }
+
+    #[test]
+    fn match_blocks_are_skipped_and_equals_is_accepted() {
+        let blocks = parse_blocks(CONFIG);
+        let host = resolve_in(&blocks, "db");
+
+        assert_eq!(host.host_name.as_deref(), Some("10.0.0.5"));
+        assert_eq!(host.user.as_deref(), Some("global-user"));
+        assert_eq!(
+            host.proxy_jump.as_deref(),
+            Some("admin@bastion:2200,gateway")
+        );
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_match("web-*", "web-prod"));
+        assert!(glob_match("db?", "db1"));
+        assert!(!glob_match("db?", "db12"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn parses_jump_hosts() {
+        assert_eq!(
+            parse_jump_host("admin@bastion:2200"),
+            Some(JumpHost {
+                user: Some("admin".to_string()),
+                host: "bastion".to_string(),
+                port: Some(2200),
+            })
+        );
+        assert_eq!(
+            parse_jump_host("ssh://[2001:db8::1]:22"),
+            Some(JumpHost {
+                user: None,
+                host: "2001:db8::1".to_string(),
+                port: Some(22),
+            })
+        );
+        assert_eq!(parse_jump_host("bastion:port"), None);
+        assert_eq!(parse_jump_host("-oProxyCommand=x"), None);
+
+        let hops = parse_proxy_jump("a@one:2200, two").unwrap();
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].to_spec(), "a@one:2200");
+        assert_eq!(hops[1].to_spec(), "two");
+        assert!(parse_proxy_jump("one,,two").is_err());
+    }
+
+    #[test]
+    fn includes_are_expanded_in_place() {
+        let dir = std::env::temp_dir().join(format!("easyftp-ssh-config-{}", std::process::id()));
+        fs::create_dir_all(dir.join("conf.d")).unwrap();
+        fs::write(dir.join("conf.d/a.conf"), "Host included\n    Port 2022\n").unwrap();
+        fs::write(dir.join("conf.d/b.conf"), "Host other\n    Port 2023\n").unwrap();
+
+        let content = "Include conf.d/*.conf\nHost main\n    Port 2024\n";
+        let blocks = parse_blocks(&expand_includes(content, &dir, 0));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(resolve_in(&blocks, "included").port, Some(2022));
+        assert_eq!(resolve_in(&blocks, "other").port, Some(2023));
+        assert_eq!(resolve_in(&blocks, "main").port, Some(2024));
+    }
+}
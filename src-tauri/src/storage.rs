@@ -1,6 +1,100 @@
+use crate::ssh_config;
 use crate::Connection;
-use std::fs;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+const BACKUP_COUNT: usize = 5;
+
+// 명령들이 동시에 실행되므로 저장 파일의 읽기-수정-쓰기는 한 번에 하나씩만 한다
+static STORAGE_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_storage() -> MutexGuard<'static, ()> {
+    // 파일은 원자적으로 교체되므로 다른 쓰기가 패닉으로 끝났어도 계속 쓸 수 있다
+    STORAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", index));
+    path.with_file_name(name)
+}
+
+fn rotate_backups(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    for index in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, index + 1))
+                .map_err(|e| format!("백업 파일 정리 실패: {}", e))?;
+        }
+    }
+
+    // 원본은 교체 직전까지 그대로 두기 위해 이동 대신 복사한다
+    fs::copy(path, backup_path(path, 1)).map_err(|e| format!("백업 파일 생성 실패: {}", e))?;
+
+    Ok(())
+}
+
+fn replace_with_temp(
+    path: &Path,
+    temp_path: &Path,
+    content: &str,
+    backup: bool,
+) -> Result<(), String> {
+    let mut file = File::create(temp_path).map_err(|e| format!("파일 생성 실패: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("파일 쓰기 실패: {}", e))?;
+    file.sync_all()
+        .map_err(|e| format!("파일 쓰기 실패: {}", e))?;
+
+    if backup {
+        rotate_backups(path)?;
+    }
+
+    fs::rename(temp_path, path).map_err(|e| format!("파일 교체 실패: {}", e))
+}
+
+/// backup이 false면 백업을 돌리지 않는다. 접속 기록처럼 자주 바뀌는 값 때문에
+/// 사용자가 직접 수정한 이전 버전이 밀려나지 않게 하기 위함이다.
+fn write_atomic(path: &Path, content: &str, backup: bool) -> Result<(), String> {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+    // 다른 쓰기의 임시 파일과 겹치지 않도록 이름마다 번호를 붙인다
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = replace_with_temp(path, &temp_path, content, backup);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("파일 읽기 실패: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "{} 파싱 실패: {} (이전 버전은 {}에 백업되어 있습니다)",
+            path.display(),
+            e,
+            backup_path(path, 1).display()
+        )
+    })
+}
 
 fn get_config_path() -> Result<PathBuf, String> {
     let config_dir = dirs::config_dir().ok_or("설정 디렉토리를 찾을 수 없습니다")?;
@@ -22,60 +116,441 @@ pub fn load_connections() -> Result<Vec<Connection>, String> {
         return Ok(Vec::new());
     }
 
-    let content = fs::read_to_string(&path).map_err(|e| format!("파일 읽기 실패: {}", e))?;
+    read_json(&path)
+}
+
+fn serialize_connections(connections: &[Connection]) -> Result<(PathBuf, String), String> {
+    let path = get_config_path()?;
+    let content = serde_json::to_string_pretty(connections)
+        .map_err(|e| format!("JSON 직렬화 실패: {}", e))?;
+
+    Ok((path, content))
+}
+
+fn write_connections(connections: &[Connection]) -> Result<(), String> {
+    let (path, content) = serialize_connections(connections)?;
+    write_atomic(&path, &content, true)
+}
+
+// 접속 횟수, 마지막 경로 같은 사용 기록만 바뀐 경우
+fn write_usage_stats(connections: &[Connection]) -> Result<(), String> {
+    let (path, content) = serialize_connections(connections)?;
+    write_atomic(&path, &content, false)
+}
+
+pub fn load_ssh_config_connections() -> Result<Vec<Connection>, String> {
+    let hosts = ssh_config::load_hosts()?;
+
+    Ok(hosts
+        .into_iter()
+        .map(|host| Connection {
+            id: format!("ssh-config:{}", host.alias),
+            name: host.alias.clone(),
+            protocol: "sftp".to_string(),
+            host: host.host_name.unwrap_or_else(|| host.alias.clone()),
+            port: host.port.unwrap_or(22),
+            username: host.user.unwrap_or_default(),
+            password: String::new(),
+            share: None,
+            identity_file: host.identity_file,
+            proxy_jump: host.proxy_jump,
+            ..Default::default()
+        })
+        .collect())
+}
 
-    let connections: Vec<Connection> =
-        serde_json::from_str(&content).map_err(|e| format!("JSON 파싱 실패: {}", e))?;
+pub fn is_same_endpoint(a: &Connection, b: &Connection) -> bool {
+    a.host == b.host && a.port == b.port && a.username == b.username && a.protocol == b.protocol
+}
+
+pub fn merge_connections(incoming: Vec<Connection>) -> Result<(), String> {
+    let _guard = lock_storage();
+    let mut connections = load_connections()?;
+
+    for connection in incoming {
+        match connections
+            .iter_mut()
+            .find(|c| is_same_endpoint(c, &connection))
+        {
+            Some(existing) => {
+                let id = std::mem::take(&mut existing.id);
+                let password = if connection.password.is_empty() {
+                    std::mem::take(&mut existing.password)
+                } else {
+                    connection.password.clone()
+                };
+                let folder = connection.folder.clone().or(existing.folder.take());
+                let tags = if connection.tags.is_empty() {
+                    std::mem::take(&mut existing.tags)
+                } else {
+                    connection.tags.clone()
+                };
+                *existing = Connection {
+                    id,
+                    password,
+                    folder,
+                    tags,
+                    favorite: existing.favorite,
+                    last_connected: existing.last_connected.take(),
+                    connect_count: existing.connect_count,
+                    last_path: existing.last_path.take(),
+                    ..connection
+                };
+            }
+            None => {
+                let mut connection = connection;
+                // 다른 기기에서 내보낸 파일 등으로 ID가 겹치면 삭제·수정이 엉뚱한 항목에 적용된다
+                if connections.iter().any(|c| c.id == connection.id) {
+                    connection.id = crate::uuid_simple();
+                }
+                connections.push(connection);
+            }
+        }
+    }
 
-    Ok(connections)
+    write_connections(&connections)
 }
 
-pub fn save_connection(connection: Connection, update_id: Option<&str>) -> Result<(), String> {
-    let mut connections = load_connections().unwrap_or_default();
+/// 저장 요청에 실제로 포함된 정리용 필드. 빠진 필드는 기존 값을 유지한다.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProvidedFields {
+    pub folder: bool,
+    pub tags: bool,
+    pub favorite: bool,
+}
+
+pub fn save_connection(
+    mut connection: Connection,
+    update_id: Option<&str>,
+    provided: ProvidedFields,
+) -> Result<(), String> {
+    let _guard = lock_storage();
+    connection.tags = normalize_tags(connection.tags);
+    let mut connections = load_connections()?;
 
     let existing_idx = if let Some(id) = update_id {
         connections.iter().position(|c| c.id == id)
     } else {
-        connections.iter().position(|c| {
-            c.host == connection.host
-                && c.port == connection.port
-                && c.username == connection.username
-                && c.protocol == connection.protocol
-        })
+        connections
+            .iter()
+            .position(|c| is_same_endpoint(c, &connection))
     };
 
     match existing_idx {
         Some(idx) => {
-            connections[idx] = connection;
+            // 사용 기록은 전용 명령으로만 변경하고, 요청에 없는 폴더·태그·즐겨찾기는 기존 값을 유지한다
+            let existing = &connections[idx];
+            let folder = if provided.folder {
+                connection.folder.clone()
+            } else {
+                existing.folder.clone()
+            };
+            let tags = if provided.tags {
+                connection.tags.clone()
+            } else {
+                existing.tags.clone()
+            };
+            let favorite = if provided.favorite {
+                connection.favorite
+            } else {
+                existing.favorite
+            };
+            connections[idx] = Connection {
+                id: existing.id.clone(),
+                folder,
+                tags,
+                favorite,
+                last_connected: existing.last_connected.clone(),
+                connect_count: existing.connect_count,
+                last_path: existing.last_path.clone(),
+                ..connection
+            };
         }
         None => {
             connections.push(connection);
         }
     }
 
-    let path = get_config_path()?;
-    let content = serde_json::to_string_pretty(&connections)
-        .map_err(|e| format!("JSON 직렬화 실패: {}", e))?;
-
-    fs::write(&path, content).map_err(|e| format!("파일 쓰기 실패: {}", e))?;
-
-    Ok(())
+    write_connections(&connections)
 }
 
 pub fn delete_connection(id: &str) -> Result<(), String> {
-    let mut connections = load_connections().unwrap_or_default();
+    let _guard = lock_storage();
+    let mut connections = load_connections()?;
 
     connections.retain(|c| c.id != id);
 
-    let path = get_config_path()?;
-    let content = serde_json::to_string_pretty(&connections)
+    write_connections(&connections)
+}
+
+#[derive(Debug, Clone)]
+pub struct BookmarkKey {
+    pub id: Option<String>,
+    pub protocol: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+}
+
+impl BookmarkKey {
+    fn matches(&self, c: &Connection) -> bool {
+        match &self.id {
+            Some(id) => &c.id == id,
+            None => {
+                c.protocol == self.protocol
+                    && c.host == self.host
+                    && c.port == self.port
+                    && c.username == self.username
+            }
+        }
+    }
+}
+
+pub fn find_connection(key: &BookmarkKey) -> Result<Option<Connection>, String> {
+    Ok(load_connections()?.into_iter().find(|c| key.matches(c)))
+}
+
+pub fn record_connection_used(key: &BookmarkKey) -> Result<(), String> {
+    let _guard = lock_storage();
+    let mut connections = load_connections()?;
+
+    let Some(conn) = connections.iter_mut().find(|c| key.matches(c)) else {
+        return Ok(());
+    };
+
+    conn.last_connected = Some(chrono::Utc::now().to_rfc3339());
+    conn.connect_count += 1;
+
+    write_usage_stats(&connections)
+}
+
+pub fn set_last_path(key: &BookmarkKey, path: &str) -> Result<(), String> {
+    let _guard = lock_storage();
+    let mut connections = load_connections()?;
+
+    let Some(conn) = connections.iter_mut().find(|c| key.matches(c)) else {
+        return Ok(());
+    };
+
+    if conn.last_path.as_deref() == Some(path) {
+        return Ok(());
+    }
+    conn.last_path = Some(path.to_string());
+
+    write_usage_stats(&connections)
+}
+
+pub fn set_favorite(id: &str, favorite: bool) -> Result<(), String> {
+    let _guard = lock_storage();
+    let mut connections = load_connections()?;
+
+    let conn = connections
+        .iter_mut()
+        .find(|c| c.id == id)
+        .ok_or("북마크를 찾을 수 없습니다")?;
+    conn.favorite = favorite;
+
+    write_connections(&connections)
+}
+
+pub fn set_tags(id: &str, tags: Vec<String>) -> Result<(), String> {
+    let _guard = lock_storage();
+    let mut connections = load_connections()?;
+
+    let conn = connections
+        .iter_mut()
+        .find(|c| c.id == id)
+        .ok_or("북마크를 찾을 수 없습니다")?;
+
+    conn.tags = normalize_tags(tags);
+
+    write_connections(&connections)
+}
+
+/// 앞뒤 공백을 없애고, 빈 태그와 대소문자만 다른 중복 태그를 뺀다. 처음 나온 표기를 남긴다.
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        let lower = tag.to_lowercase();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.to_lowercase() == lower) {
+            normalized.push(tag);
+        }
+    }
+
+    normalized
+}
+
+pub fn find_by_tag(tag: &str) -> Result<Vec<Connection>, String> {
+    let tag = tag.trim().to_lowercase();
+
+    Ok(load_connections()?
+        .into_iter()
+        .filter(|c| c.tags.iter().any(|t| t.to_lowercase() == tag))
+        .collect())
+}
+
+pub fn load_tags() -> Result<Vec<String>, String> {
+    let mut tags: Vec<String> = load_connections()?
+        .into_iter()
+        .flat_map(|c| c.tags)
+        .collect();
+
+    // 대소문자만 다른 태그는 하나로 보여준다
+    tags.sort_by_key(|t| t.to_lowercase());
+    tags.dedup_by_key(|t| t.to_lowercase());
+
+    Ok(tags)
+}
+
+fn get_folders_path() -> Result<PathBuf, String> {
+    let config_path = get_config_path()?;
+    Ok(config_path.with_file_name("folders.json"))
+}
+
+pub fn normalize_folder(folder: &str) -> Option<String> {
+    let folder = folder
+        .split('/')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    Some(folder).filter(|f| !f.is_empty())
+}
+
+fn is_in_folder(folder: &str, parent: &str) -> bool {
+    folder == parent
+        || folder
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn read_stored_folders() -> Result<Vec<String>, String> {
+    let path = get_folders_path()?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    read_json(&path)
+}
+
+fn write_folders(folders: &[String]) -> Result<(), String> {
+    let path = get_folders_path()?;
+    let content = serde_json::to_string_pretty(folders)
         .map_err(|e| format!("JSON 직렬화 실패: {}", e))?;
 
-    fs::write(&path, content).map_err(|e| format!("파일 쓰기 실패: {}", e))?;
+    write_atomic(&path, &content, true)
+}
+
+pub fn load_folders() -> Result<Vec<String>, String> {
+    let mut folders = read_stored_folders()?;
+
+    for conn in load_connections()? {
+        if let Some(folder) = conn.folder {
+            folders.push(folder);
+        }
+    }
+
+    // 중첩 폴더의 상위 폴더도 목록에 포함한다
+    let mut all: Vec<String> = Vec::new();
+    for folder in folders {
+        let mut path = String::new();
+        for segment in folder.split('/') {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(segment);
+            if !all.contains(&path) {
+                all.push(path.clone());
+            }
+        }
+    }
+
+    all.sort();
+    Ok(all)
+}
+
+pub fn create_folder(folder: &str) -> Result<(), String> {
+    let _guard = lock_storage();
+    let folder = normalize_folder(folder).ok_or("폴더 이름이 비어 있습니다")?;
+    let mut folders = read_stored_folders()?;
+
+    if !folders.contains(&folder) {
+        folders.push(folder);
+        folders.sort();
+        write_folders(&folders)?;
+    }
 
     Ok(())
 }
 
+pub fn rename_folder(from: &str, to: &str) -> Result<(), String> {
+    let _guard = lock_storage();
+    let from = normalize_folder(from).ok_or("폴더 이름이 비어 있습니다")?;
+    let to = normalize_folder(to).ok_or("폴더 이름이 비어 있습니다")?;
+
+    if is_in_folder(&to, &from) {
+        return Err("폴더를 자기 자신의 하위로 옮길 수 없습니다".to_string());
+    }
+
+    let rename = |folder: &str| {
+        if is_in_folder(folder, &from) {
+            format!("{}{}", to, &folder[from.len()..])
+        } else {
+            folder.to_string()
+        }
+    };
+
+    let mut folders: Vec<String> = read_stored_folders()?.iter().map(|f| rename(f)).collect();
+    folders.sort();
+    folders.dedup();
+    write_folders(&folders)?;
+
+    let mut connections = load_connections()?;
+    for conn in connections.iter_mut() {
+        if let Some(folder) = &conn.folder {
+            conn.folder = Some(rename(folder));
+        }
+    }
+
+    write_connections(&connections)
+}
+
+pub fn delete_folder(folder: &str) -> Result<(), String> {
+    let _guard = lock_storage();
+    let folder = normalize_folder(folder).ok_or("폴더 이름이 비어 있습니다")?;
+    let parent = folder.rsplit_once('/').map(|(parent, _)| parent.to_string());
+
+    let mut folders = read_stored_folders()?;
+    folders.retain(|f| !is_in_folder(f, &folder));
+    write_folders(&folders)?;
+
+    // 삭제된 폴더의 북마크는 상위 폴더로 옮긴다
+    let mut connections = load_connections()?;
+    for conn in connections.iter_mut() {
+        if conn.folder.as_deref().is_some_and(|f| is_in_folder(f, &folder)) {
+            conn.folder = parent.clone();
+        }
+    }
+
+    write_connections(&connections)
+}
+
+pub fn move_connections(ids: &[String], folder: Option<&str>) -> Result<(), String> {
+    let _guard = lock_storage();
+    let folder = folder.and_then(normalize_folder);
+    let mut connections = load_connections()?;
+
+    for conn in connections.iter_mut().filter(|c| ids.contains(&c.id)) {
+        conn.folder = folder.clone();
+    }
+
+    write_connections(&connections)
+}
+
 fn get_settings_path() -> Result<PathBuf, String> {
     let config_dir = dirs::config_dir().ok_or("설정 디렉토리를 찾을 수 없습니다")?;
     let app_config_dir = config_dir.join("easyftp");
@@ -88,36 +563,206 @@ fn get_settings_path() -> Result<PathBuf, String> {
     Ok(app_config_dir.join("settings.json"))
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Default)]
-struct Settings {
-    editor_path: String,
+pub const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    Ask,
+    Overwrite,
+    Skip,
+    Rename,
 }
 
-pub fn get_editor_path() -> Result<String, String> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub editor_path: String,
+    pub default_download_dir: Option<String>,
+    pub transfer_concurrency: u32,
+    pub overwrite_policy: OverwritePolicy,
+    pub show_hidden_files: bool,
+    pub theme: Theme,
+    pub locale: String,
+    pub connect_timeout_secs: u64,
+    pub transfer_timeout_secs: u64,
+    pub listing_cache_ttl_secs: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            editor_path: String::new(),
+            default_download_dir: None,
+            transfer_concurrency: 3,
+            overwrite_policy: OverwritePolicy::Ask,
+            show_hidden_files: false,
+            theme: Theme::System,
+            locale: "ko".to_string(),
+            connect_timeout_secs: 15,
+            transfer_timeout_secs: 60,
+            listing_cache_ttl_secs: 30,
+        }
+    }
+}
+
+fn migrate_settings(mut value: serde_json::Value) -> Result<serde_json::Value, String> {
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+
+    if version > SETTINGS_VERSION as u64 {
+        return Err(format!(
+            "더 최신 버전의 설정 파일입니다 (v{}). 앱을 업데이트해주세요.",
+            version
+        ));
+    }
+
+    let object = value.as_object_mut().ok_or("설정 파일 형식이 올바르지 않습니다")?;
+
+    // v0: { "editor_path": "..." } 형태의 단일 필드 설정
+    if version < 1 {
+        if let Some(editor_path) = object.remove("editor_path") {
+            object.insert("editorPath".to_string(), editor_path);
+        }
+    }
+
+    object.insert("version".to_string(), SETTINGS_VERSION.into());
+
+    Ok(value)
+}
+
+fn validate_settings(settings: &Settings) -> Result<(), String> {
+    if !(1..=16).contains(&settings.transfer_concurrency) {
+        return Err("동시 전송 수는 1에서 16 사이여야 합니다".to_string());
+    }
+
+    if settings.connect_timeout_secs == 0 || settings.transfer_timeout_secs == 0 {
+        return Err("타임아웃은 0보다 커야 합니다".to_string());
+    }
+
+    Ok(())
+}
+
+fn write_settings(settings: &Settings) -> Result<(), String> {
+    let path = get_settings_path()?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("설정 직렬화 실패: {}", e))?;
+
+    write_atomic(&path, &content, true)
+}
+
+pub fn load_settings() -> Result<Settings, String> {
+    // 예전 형식이면 변환해서 다시 쓰므로 잠근다
+    let _guard = lock_storage();
+    read_settings()
+}
+
+fn read_settings() -> Result<Settings, String> {
     let path = get_settings_path()?;
 
     if !path.exists() {
-        return Ok(String::new());
+        return Ok(Settings::default());
+    }
+
+    let value: serde_json::Value = read_json(&path)?;
+    let needs_migration = value.get("version").and_then(|v| v.as_u64())
+        != Some(SETTINGS_VERSION as u64);
+
+    let settings: Settings = serde_json::from_value(migrate_settings(value)?)
+        .map_err(|e| format!("설정 파싱 실패: {}", e))?;
+
+    if needs_migration {
+        write_settings(&settings)?;
+    }
+
+    Ok(settings)
+}
+
+pub fn update_settings(patch: serde_json::Value) -> Result<Settings, String> {
+    let _guard = lock_storage();
+    let patch = match patch {
+        serde_json::Value::Object(patch) => patch,
+        _ => return Err("설정 변경 형식이 올바르지 않습니다".to_string()),
+    };
+
+    let mut value =
+        serde_json::to_value(read_settings()?).map_err(|e| format!("설정 직렬화 실패: {}", e))?;
+
+    if let Some(object) = value.as_object_mut() {
+        for (key, field) in patch {
+            if key != "version" {
+                object.insert(key, field);
+            }
+        }
     }
 
-    let content = fs::read_to_string(&path).map_err(|e| format!("설정 파일 읽기 실패: {}", e))?;
     let settings: Settings =
-        serde_json::from_str(&content).map_err(|e| format!("설정 파싱 실패: {}", e))?;
+        serde_json::from_value(value).map_err(|e| format!("잘못된 설정 값: {}", e))?;
+    validate_settings(&settings)?;
+
+    write_settings(&settings)?;
 
-    Ok(settings.editor_path)
+    Ok(settings)
+}
+
+pub fn get_editor_path() -> Result<String, String> {
+    Ok(load_settings()?.editor_path)
 }
 
 pub fn set_editor_path(editor_path: &str) -> Result<(), String> {
-    let path = get_settings_path()?;
+    update_settings(serde_json::json!({ "editorPath": editor_path }))?;
+    Ok(())
+}
 
-    let settings = Settings {
-        editor_path: editor_path.to_string(),
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_tags_dedups_case_insensitively() {
+        let tags = vec![
+            " Prod ".to_string(),
+            "prod".to_string(),
+            "".to_string(),
+            "Web".to_string(),
+            "PROD".to_string(),
+            "web ".to_string(),
+            "운영".to_string(),
+        ];
+
+        assert_eq!(normalize_tags(tags), ["Prod", "Web", "운영"]);
+    }
 
-    let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("설정 직렬화 실패: {}", e))?;
+    #[test]
+    fn write_atomic_allows_concurrent_writers() {
+        let dir = std::env::temp_dir().join(format!("easyftp-storage-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("connections.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|index| {
+                let path = path.clone();
+                std::thread::spawn(move || write_atomic(&path, &format!("[{}]", index), false))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
 
-    fs::write(&path, content).map_err(|e| format!("설정 저장 실패: {}", e))?;
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(serde_json::from_str::<Vec<u32>>(&content).is_ok());
+        // 임시 파일이 남지 않는다
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
 
-    Ok(())
+        fs::remove_dir_all(&dir).ok();
+    }
 }
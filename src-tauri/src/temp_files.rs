@@ -0,0 +1,211 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+// 이보다 오래된 임시 파일은 이전 실행에서 남은 것으로 보고 시작할 때 지운다
+const STALE_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+const COMPRESS_PREFIX: &str = "easyftp_compress_";
+const INSTANCE_PREFIX: &str = "instance-";
+
+// 실행 중인 동안 잠가 두는 파일. 다른 인스턴스가 이 프로세스의 세션 폴더를 지우지 않게 한다
+static INSTANCE_LOCK: OnceLock<File> = OnceLock::new();
+
+// 장치 이름이라 Windows에서 파일 이름으로 쓸 수 없다 (확장자가 붙어도 마찬가지)
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+pub fn root() -> PathBuf {
+    std::env::temp_dir().join("easyftp")
+}
+
+/// 세션마다 고유한 폴더 이름. 프로세스 ID를 붙여 동시에 실행된 앱끼리도 겹치지 않는다.
+pub fn new_session_id() -> String {
+    format!("{}-{}", std::process::id(), crate::uuid_simple())
+}
+
+fn session_dir(session_id: &str) -> PathBuf {
+    root().join(format!("session-{}", session_id))
+}
+
+fn instance_lock_path(pid: u32) -> PathBuf {
+    root().join(format!("{}{}.lock", INSTANCE_PREFIX, pid))
+}
+
+/// 이 프로세스가 살아 있다는 표시로 잠금 파일을 잡아 둔다. 세션을 만들기 전에 불러야 한다.
+pub fn lock_instance() {
+    if INSTANCE_LOCK.get().is_some() || fs::create_dir_all(root()).is_err() {
+        return;
+    }
+
+    let Ok(file) = File::create(instance_lock_path(std::process::id())) else {
+        return;
+    };
+    if file.try_lock().is_ok() {
+        let _ = INSTANCE_LOCK.set(file);
+    }
+}
+
+/// 잠금 파일을 잡고 있는 프로세스가 있으면 살아 있는 것으로 본다
+fn is_instance_running(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+
+    match File::open(instance_lock_path(pid)) {
+        Ok(file) => !matches!(file.try_lock(), Ok(())),
+        Err(_) => false,
+    }
+}
+
+/// "session-<pid>-<id>" 또는 "instance-<pid>.lock"에서 프로세스 ID를 꺼낸다
+fn owner_pid(name: &str) -> Option<u32> {
+    let rest = name
+        .strip_prefix("session-")
+        .or_else(|| name.strip_prefix(INSTANCE_PREFIX))?;
+    rest.split(['-', '.']).next()?.parse().ok()
+}
+
+pub fn short_hash(text: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, text.as_bytes());
+    digest.as_ref()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 경로 구분자, 상위 폴더 참조, 제어 문자와 Windows 예약 문자를 제거한 파일 이름
+pub fn sanitize_file_name(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or("");
+
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').to_string();
+
+    if cleaned.is_empty() || cleaned == "." || cleaned == ".." {
+        return "file".to_string();
+    }
+
+    let (stem, extension) = match cleaned.find('.') {
+        Some(index) => cleaned.split_at(index),
+        None => (cleaned.as_str(), ""),
+    };
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.trim_end().eq_ignore_ascii_case(reserved))
+    {
+        return format!("{}_{}", stem, extension);
+    }
+
+    cleaned
+}
+
+/// 원격 경로별 임시 파일 위치. 다른 폴더나 서버의 같은 이름 파일이 서로 덮어쓰지 않도록
+/// 세션 폴더 아래에 원격 경로의 해시로 폴더를 나눈다.
+pub fn path_for(session_id: &str, remote_path: &str, file_name: &str) -> Result<PathBuf, String> {
+    Ok(dir_for(session_id, remote_path)?.join(sanitize_file_name(file_name)))
+}
+
+/// 세션 폴더 아래에 key의 해시로 만든 작업 폴더
+pub fn dir_for(session_id: &str, key: &str) -> Result<PathBuf, String> {
+    let dir = session_dir(session_id).join(short_hash(key));
+    fs::create_dir_all(&dir).map_err(|e| format!("임시 폴더 생성 실패: {}", e))?;
+
+    Ok(dir)
+}
+
+pub fn remove_session(session_id: &str) {
+    let _ = fs::remove_dir_all(session_dir(session_id));
+}
+
+fn is_stale(path: &std::path::Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|age| age > STALE_AGE)
+}
+
+fn remove_path(path: &std::path::Path) {
+    if path.is_dir() {
+        let _ = fs::remove_dir_all(path);
+    } else {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub fn cleanup_stale() {
+    if let Ok(entries) = fs::read_dir(root()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_session = name.starts_with("session-");
+            let is_instance = name.starts_with(INSTANCE_PREFIX);
+
+            // 실행 중인 인스턴스의 세션과 잠금 파일은 건드리지 않는다
+            let running = owner_pid(&name).is_some_and(is_instance_running);
+            if (is_session || is_instance) && running {
+                continue;
+            }
+
+            // 주인이 없는 세션 폴더와, 세션 폴더 밖에 이전 버전이 평평하게 저장하던 임시 파일을 지운다.
+            // 프로세스 ID가 없는 이전 형식의 세션 폴더는 오래된 경우에만 지운다.
+            if !is_session || owner_pid(&name).is_some() || is_stale(&path) {
+                remove_path(&path);
+            }
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(std::env::temp_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(COMPRESS_PREFIX)
+                && is_stale(&path)
+            {
+                remove_path(&path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_file_name_replaces_invalid_characters() {
+        assert_eq!(sanitize_file_name("a:b?.txt"), "a_b_.txt");
+        assert_eq!(sanitize_file_name("name. "), "name");
+        assert_eq!(sanitize_file_name(".."), "file");
+        assert_eq!(sanitize_file_name(""), "file");
+    }
+
+    #[test]
+    fn sanitize_file_name_avoids_windows_reserved_names() {
+        assert_eq!(sanitize_file_name("CON"), "CON_");
+        assert_eq!(sanitize_file_name("nul.txt"), "nul_.txt");
+        assert_eq!(sanitize_file_name("Com1.tar.gz"), "Com1_.tar.gz");
+        assert_eq!(sanitize_file_name("lpt9"), "lpt9_");
+        assert_eq!(sanitize_file_name("console.log"), "console.log");
+        assert_eq!(sanitize_file_name("COM10"), "COM10");
+    }
+
+    #[test]
+    fn owner_pid_reads_session_and_lock_names() {
+        assert_eq!(owner_pid("session-1234-abcd"), Some(1234));
+        assert_eq!(owner_pid("instance-42.lock"), Some(42));
+        assert_eq!(owner_pid("session-abcd"), None);
+        assert_eq!(owner_pid("other"), None);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{ActiveConnection, AppState};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::TryLockError;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(30);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecOutputEvent {
+    pub exec_id: String,
+    pub stream: &'static str,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecResult {
+    pub exit_status: Option<i32>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellOutputEvent {
+    pub shell_id: String,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellExitEvent {
+    pub shell_id: String,
+    pub exit_status: Option<i32>,
+}
+
+/// 청크 경계에서 잘린 멀티바이트 문자는 다음 청크와 합쳐 디코딩한다
+pub fn decode_utf8(pending: &mut Vec<u8>, data: &[u8]) -> String {
+    pending.extend_from_slice(data);
+
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => {
+            let text = String::from_utf8_lossy(pending).to_string();
+            pending.clear();
+            return text;
+        }
+    };
+
+    let rest = pending.split_off(valid);
+    let text = std::mem::replace(pending, rest);
+    String::from_utf8(text).unwrap_or_default()
+}
+
+/// 열린 셸의 출력을 주기적으로 읽어 이벤트로 보낸다.
+/// 전송 등으로 연결이 사용 중이면 그 작업이 끝날 때까지 기다린다.
+pub fn spawn_shell_pump(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut pending: HashMap<String, Vec<u8>> = HashMap::new();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let state = app.state::<AppState>();
+            let mut conn_guard = match state.connection.try_lock() {
+                Ok(guard) => guard,
+                Err(TryLockError::WouldBlock) => continue,
+                Err(TryLockError::Poisoned(_)) => break,
+            };
+
+            let reads = match &mut *conn_guard {
+                ActiveConnection::Sftp(sftp) if sftp.has_shells() => sftp.poll_shells(),
+                _ => {
+                    // 연결을 잡은 채로 내려야 open_shell과 엇갈리지 않는다
+                    state.shell_pump_running.store(false, Ordering::SeqCst);
+                    break;
+                }
+            };
+            drop(conn_guard);
+
+            for read in reads {
+                let buffer = pending.entry(read.shell_id.clone()).or_default();
+                let data = decode_utf8(buffer, &read.data);
+
+                if !data.is_empty() {
+                    let _ = app.emit(
+                        "shell-output",
+                        ShellOutputEvent {
+                            shell_id: read.shell_id.clone(),
+                            data,
+                        },
+                    );
+                }
+
+                if read.closed {
+                    pending.remove(&read.shell_id);
+                    let _ = app.emit(
+                        "shell-exit",
+                        ShellExitEvent {
+                            shell_id: read.shell_id,
+                            exit_status: read.exit_status,
+                        },
+                    );
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,231 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat};
+use serde::Serialize;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// 요청에 크기가 없을 때의 긴 변 길이
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+const MAX_THUMBNAIL_SIZE: u32 = 1024;
+const JPEG_QUALITY: u8 = 80;
+// 만든 지 오래된 썸네일은 시작할 때 지운다
+const CACHE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thumbnail {
+    pub data: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("easyftp")
+        .join("thumbnails")
+}
+
+pub fn clamp_size(size: Option<u32>) -> u32 {
+    size.filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_THUMBNAIL_SIZE)
+        .min(MAX_THUMBNAIL_SIZE)
+}
+
+/// 서버, 원격 경로, 수정 시각, 크기가 모두 같아야 같은 썸네일로 본다
+pub fn cache_key(
+    server: &str,
+    remote_path: &str,
+    modified: &str,
+    size: u64,
+    max_size: u32,
+) -> String {
+    crate::temp_files::short_hash(&format!(
+        "{}\n{}\n{}\n{}\n{}",
+        server, remote_path, modified, size, max_size
+    ))
+}
+
+fn cache_path(key: &str) -> PathBuf {
+    cache_dir().join(format!("{}.thumb", key))
+}
+
+/// 캐시 파일은 썸네일·원본 크기 16바이트, MIME 길이 1바이트, MIME, 이미지 데이터 순으로 저장한다
+fn encode_cache(thumbnail: &Thumbnail, bytes: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(bytes.len() + 32);
+    for value in [
+        thumbnail.width,
+        thumbnail.height,
+        thumbnail.original_width,
+        thumbnail.original_height,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.push(thumbnail.mime_type.len() as u8);
+    data.extend_from_slice(thumbnail.mime_type.as_bytes());
+    data.extend_from_slice(bytes);
+    data
+}
+
+pub fn load_cached(key: &str) -> Option<Thumbnail> {
+    decode_cache(&fs::read(cache_path(key)).ok()?)
+}
+
+/// 잘렸거나 형식이 맞지 않는 캐시 파일은 None을 돌려줘 썸네일을 다시 만들게 한다
+fn decode_cache(data: &[u8]) -> Option<Thumbnail> {
+    let header = data.get(..17)?;
+    let value = |index: usize| {
+        let start = index * 4;
+        u32::from_le_bytes([
+            header[start],
+            header[start + 1],
+            header[start + 2],
+            header[start + 3],
+        ])
+    };
+
+    let mime_end = 17 + header[16] as usize;
+    let mime_type = std::str::from_utf8(data.get(17..mime_end)?).ok()?;
+    let bytes = data.get(mime_end..)?;
+
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    Some(Thumbnail {
+        data: STANDARD.encode(bytes),
+        mime_type: mime_type.to_string(),
+        width: value(0),
+        height: value(1),
+        original_width: value(2),
+        original_height: value(3),
+    })
+}
+
+fn encode_image(image: &DynamicImage) -> Result<(Vec<u8>, &'static str), String> {
+    let mut bytes = Vec::new();
+
+    // 투명도가 있으면 PNG, 없으면 더 작은 JPEG로 저장한다
+    if image.color().has_alpha() {
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|e| format!("썸네일 인코딩 실패: {}", e))?;
+        Ok((bytes, "image/png"))
+    } else {
+        let encoder = JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY);
+        image
+            .to_rgb8()
+            .write_with_encoder(encoder)
+            .map_err(|e| format!("썸네일 인코딩 실패: {}", e))?;
+        Ok((bytes, "image/jpeg"))
+    }
+}
+
+/// 이미지를 디코딩해 긴 변이 max_size 이하가 되도록 줄인다. 키가 있으면 결과를 캐시에 저장한다.
+pub fn generate(data: &[u8], max_size: u32, key: Option<&str>) -> Result<Thumbnail, String> {
+    let format =
+        image::guess_format(data).map_err(|_| "이미지 형식을 알 수 없습니다".to_string())?;
+    if !matches!(
+        format,
+        ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP
+    ) {
+        return Err(format!("지원하지 않는 이미지 형식: {:?}", format));
+    }
+
+    let image = image::load_from_memory_with_format(data, format)
+        .map_err(|e| format!("이미지 디코딩 실패: {}", e))?;
+
+    let (original_width, original_height) = (image.width(), image.height());
+    // 이미 작은 이미지는 키우지 않는다
+    let resized = if original_width <= max_size && original_height <= max_size {
+        image
+    } else {
+        image.thumbnail(max_size, max_size)
+    };
+
+    let (bytes, mime_type) = encode_image(&resized)?;
+
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    let thumbnail = Thumbnail {
+        data: STANDARD.encode(&bytes),
+        mime_type: mime_type.to_string(),
+        width: resized.width(),
+        height: resized.height(),
+        original_width,
+        original_height,
+    };
+
+    // 캐시 저장에 실패해도 썸네일은 돌려준다
+    if let Some(key) = key {
+        if fs::create_dir_all(cache_dir()).is_ok() {
+            let _ = fs::write(cache_path(key), encode_cache(&thumbnail, &bytes));
+        }
+    }
+
+    Ok(thumbnail)
+}
+
+pub fn cleanup_cache() {
+    let Ok(entries) = fs::read_dir(cache_dir()) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > CACHE_MAX_AGE);
+        if expired {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    fn sample() -> (Thumbnail, Vec<u8>) {
+        let bytes = vec![0x89, b'P', b'N', b'G', 0, 1, 2, 3];
+        let thumbnail = Thumbnail {
+            data: STANDARD.encode(&bytes),
+            mime_type: "image/png".to_string(),
+            width: 256,
+            height: 128,
+            original_width: 600,
+            original_height: 300,
+        };
+        (thumbnail, bytes)
+    }
+
+    #[test]
+    fn cache_round_trip() {
+        let (thumbnail, bytes) = sample();
+        let cached = decode_cache(&encode_cache(&thumbnail, &bytes)).unwrap();
+
+        assert_eq!(cached.data, thumbnail.data);
+        assert_eq!(cached.mime_type, "image/png");
+        assert_eq!((cached.width, cached.height), (256, 128));
+        assert_eq!((cached.original_width, cached.original_height), (600, 300));
+    }
+
+    #[test]
+    fn rejects_corrupt_cache() {
+        let (thumbnail, bytes) = sample();
+        let mut data = encode_cache(&thumbnail, &bytes);
+
+        // 헤더가 잘린 경우
+        assert!(decode_cache(&[]).is_none());
+        assert!(decode_cache(&data[..10]).is_none());
+        // MIME 길이가 파일보다 긴 경우
+        assert!(decode_cache(&data[..20]).is_none());
+        // MIME이 UTF-8이 아닌 경우
+        data[17] = 0xFF;
+        assert!(decode_cache(&data).is_none());
+    }
+}
@@ -1,15 +1,17 @@
 import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { open, save, confirm } from '@tauri-apps/plugin-dialog';
 import { ScaleControl } from './components/ScaleControl';
 import { BookmarkList } from './components/BookmarkList';
 import { ConnectionModal } from './components/ConnectionModal';
 import { CompressModal } from './components/CompressModal';
 import { FileList } from './components/FileList';
+import { PassphraseModal } from './components/PassphraseModal';
 import { PreviewPanel } from './components/PreviewPanel';
 import { SettingsModal } from './components/SettingsModal';
 import { useScale } from './hooks/useScale';
-import type { Connection, FileEntry, Protocol } from './types';
+import type { CompressSummary, ConnectInfo, Connection, FileEntry, ImportReport, ListChunk, Protocol, TextPreview } from './types';
 import './App.css';
 
 function App() {
@@ -33,12 +35,14 @@ function App() {
   const [splitPosition, setSplitPosition] = useState(50);
   const [isDragging, setIsDragging] = useState(false);
   const mainRef = useRef<HTMLElement>(null);
+  const listRequestRef = useRef<string | null>(null);
   
   const [showSettings, setShowSettings] = useState(false);
   const [showConnectionModal, setShowConnectionModal] = useState(false);
   const [editingBookmark, setEditingBookmark] = useState<Connection | null>(null);
   const [recentPaths, setRecentPaths] = useState<string[]>([]);
   const [showCompressModal, setShowCompressModal] = useState(false);
+  const [passphraseRequest, setPassphraseRequest] = useState<{ mode: 'export' | 'import'; filePath: string } | null>(null);
   const [compressTarget, setCompressTarget] = useState<FileEntry | null>(null);
 
   useEffect(() => {
@@ -114,16 +118,16 @@ function App() {
     }
   };
 
-  const handleConnect = async (connection: Omit<Connection, 'id'>) => {
+  const handleConnect = async (connection: Omit<Connection, 'id'> & { id?: string }) => {
     setLoading(true);
     setError(null);
     
     try {
-      await invoke('connect', { connection });
+      const info = await invoke<ConnectInfo>('connect', { connection });
       setConnected(true);
       setCurrentProtocol(connection.protocol);
       setCurrentHost(connection.host);
-      await listFiles('/');
+      await openInitialPath(info);
       
       await invoke('save_connection', { connection });
       await loadSavedConnections();
@@ -149,16 +153,78 @@ function App() {
     }
   };
 
-  const listFiles = async (path: string) => {
+  // 서버에서 읽는 대로 먼저 보여주고, 정렬된 목록이 오면 교체한다
+  const loadFiles = async (path: string, refresh = false) => {
+    const requestId = `${Date.now()}-${Math.random().toString(36).slice(2)}`;
+    listRequestRef.current = requestId;
+    
+    let streamed: FileEntry[] = [];
+    let sorted: FileEntry[] = [];
+    let shown = false;
+    let finish: () => void = () => {};
+    const finished = new Promise<void>(resolve => { finish = resolve; });
+    
+    const unlisten = await listen<ListChunk>('list-files-chunk', (event) => {
+      const chunk = event.payload;
+      if (chunk.requestId !== requestId) return;
+      // 다른 폴더로 이동했으면 이 목록은 버린다
+      if (listRequestRef.current !== requestId) {
+        if (chunk.done) finish();
+        return;
+      }
+      
+      if (!shown) {
+        shown = true;
+        setCurrentPath(path);
+        setLoading(false);
+      }
+      
+      if (chunk.sorted) {
+        sorted = chunk.offset === 0 ? chunk.entries : [...sorted, ...chunk.entries];
+        if (chunk.done) {
+          setFiles(sorted);
+          finish();
+        }
+      } else {
+        streamed = [...streamed, ...chunk.entries];
+        setFiles(streamed);
+      }
+    });
+    
+    try {
+      await invoke<number>('list_files_stream', { path, options: {}, requestId, refresh });
+      await finished;
+    } finally {
+      unlisten();
+    }
+    
+    if (listRequestRef.current !== requestId) return;
+    setCurrentPath(path);
+    setRecentPaths(prev => {
+      const filtered = prev.filter(p => p !== path);
+      return [path, ...filtered].slice(0, 20);
+    });
+  };
+
+  // 마지막으로 보던 경로가 지워졌으면 설정된 시작 경로, 그다음 루트로 연다
+  const openInitialPath = async (info: ConnectInfo) => {
+    const candidates = [info.initialPath, ...info.fallbackPaths];
+    for (const [index, path] of candidates.entries()) {
+      try {
+        await loadFiles(path);
+        return;
+      } catch (err) {
+        if (index === candidates.length - 1) {
+          setError(err as string);
+        }
+      }
+    }
+  };
+
+  const listFiles = async (path: string, refresh = false) => {
     setLoading(true);
     try {
-      const entries = await invoke<FileEntry[]>('list_files', { path });
-      setFiles(entries);
-      setCurrentPath(path);
-      setRecentPaths(prev => {
-        const filtered = prev.filter(p => p !== path);
-        return [path, ...filtered].slice(0, 20);
-      });
+      await loadFiles(path, refresh);
     } catch (err) {
       setError(err as string);
     } finally {
@@ -229,7 +295,7 @@ function App() {
   };
 
   const handleRefresh = () => {
-    listFiles(currentPath);
+    listFiles(currentPath, true);
   };
 
   const handleDelete = async (file: FileEntry) => {
@@ -305,15 +371,7 @@ function App() {
   };
 
   const handleConnectFromBookmark = async (bookmark: Connection) => {
-    await handleConnect({
-      name: bookmark.name,
-      protocol: bookmark.protocol,
-      host: bookmark.host,
-      port: bookmark.port,
-      username: bookmark.username,
-      password: bookmark.password,
-      share: bookmark.share,
-    });
+    await handleConnect(bookmark);
   };
 
   const handleDuplicateBookmark = async (bookmark: Connection) => {
@@ -343,21 +401,31 @@ function App() {
       });
       
       if (filePath) {
-        const exportData = savedConnections.map(conn => ({
-          name: conn.name,
-          protocol: conn.protocol,
-          host: conn.host,
-          port: conn.port,
-          username: conn.username,
-          share: conn.share,
-        }));
-        await invoke('export_bookmarks', { filePath, data: JSON.stringify(exportData, null, 2) });
+        setPassphraseRequest({ mode: 'export', filePath });
       }
     } catch (err) {
       setError(err as string);
     }
   };
 
+  const importBookmarks = async (filePath: string, passphrase: string | null) => {
+    try {
+      const report = await invoke<ImportReport>('import_bookmarks', { filePath, passphrase });
+      await loadSavedConnections();
+      
+      if (report.skipped.length > 0) {
+        setError(report.skipped.map(s => `${s.name}: ${s.reason}`).join('\n'));
+      }
+    } catch (err) {
+      // 암호화된 파일이면 암호를 물어본 뒤 다시 가져온다
+      if (passphrase === null && String(err).startsWith('암호화된 북마크 파일입니다')) {
+        setPassphraseRequest({ mode: 'import', filePath });
+        return;
+      }
+      setError(err as string);
+    }
+  };
+
   const handleImportBookmarks = async () => {
     try {
       const filePath = await open({
@@ -366,29 +434,29 @@ function App() {
       });
       
       if (filePath) {
-        const content = await invoke<string>('import_bookmarks', { filePath });
-        const bookmarks = JSON.parse(content);
-        
-        for (const bookmark of bookmarks) {
-          await invoke('save_connection', { 
-            connection: {
-              name: bookmark.name,
-              protocol: bookmark.protocol,
-              host: bookmark.host,
-              port: bookmark.port,
-              username: bookmark.username,
-              password: bookmark.password || '',
-              share: bookmark.share,
-            }
-          });
-        }
-        await loadSavedConnections();
+        await importBookmarks(filePath, null);
       }
     } catch (err) {
       setError(err as string);
     }
   };
 
+  const handlePassphraseSubmit = async (passphrase: string | null) => {
+    if (!passphraseRequest) return;
+    const { mode, filePath } = passphraseRequest;
+    
+    if (mode === 'import') {
+      await importBookmarks(filePath, passphrase);
+      return;
+    }
+    
+    try {
+      await invoke('export_bookmarks', { filePath, passphrase });
+    } catch (err) {
+      setError(err as string);
+    }
+  };
+
   const handleCompressRequest = (file: FileEntry) => {
     setCompressTarget(file);
     setShowCompressModal(true);
@@ -409,12 +477,23 @@ function App() {
       
       if (savePath) {
         setLoading(true);
-        await invoke('compress_file', {
+        const summary = await invoke<CompressSummary>('compress_file', {
           remotePath: compressTarget.path,
           localPath: savePath,
           format,
           isDirectory: compressTarget.isDirectory,
         });
+        
+        const notices = [];
+        if (summary.unreadable.length > 0) {
+          notices.push(`읽을 수 없어 제외한 폴더:\n${summary.unreadable.join('\n')}`);
+        }
+        if (summary.changed.length > 0) {
+          notices.push(`압축하는 동안 크기가 바뀌어 앞부분만 담은 파일:\n${summary.changed.join('\n')}`);
+        }
+        if (notices.length > 0) {
+          setError(notices.join('\n\n'));
+        }
       }
     } catch (err) {
       setError(err as string);
@@ -447,8 +526,8 @@ function App() {
                         ext === 'bmp' ? 'image/bmp' : 'image/png';
         setPreviewImageData(`data:${mimeType};base64,${base64}`);
       } else {
-        const text = await invoke<string>('preview_file_text', { remotePath: file.path });
-        setPreviewContent(text);
+        const preview = await invoke<TextPreview>('preview_file_text', { remotePath: file.path });
+        setPreviewContent(preview.text);
       }
     } catch (err) {
       setError(err as string);
@@ -640,6 +719,14 @@ function App() {
         onCompress={handleCompress}
         scale={scale}
       />
+
+      <PassphraseModal
+        isOpen={passphraseRequest !== null}
+        mode={passphraseRequest?.mode ?? 'export'}
+        onClose={() => setPassphraseRequest(null)}
+        onSubmit={handlePassphraseSubmit}
+        scale={scale}
+      />
     </div>
   );
 }
@@ -0,0 +1,98 @@
+import { useState, useEffect } from 'react';
+
+interface PassphraseModalProps {
+  isOpen: boolean;
+  mode: 'export' | 'import';
+  onClose: () => void;
+  onSubmit: (passphrase: string | null) => void;
+  scale: number;
+}
+
+export function PassphraseModal({
+  isOpen,
+  mode,
+  onClose,
+  onSubmit,
+  scale,
+}: PassphraseModalProps) {
+  const [passphrase, setPassphrase] = useState('');
+  const [confirmation, setConfirmation] = useState('');
+
+  useEffect(() => {
+    if (isOpen) {
+      setPassphrase('');
+      setConfirmation('');
+    }
+  }, [isOpen]);
+
+  if (!isOpen) return null;
+
+  const baseFontSize = 13 * scale;
+  const isExport = mode === 'export';
+  const mismatch = isExport && passphrase !== confirmation;
+  const canSubmit = isExport ? !mismatch : passphrase.length > 0;
+
+  const handleSubmit = () => {
+    if (!canSubmit) return;
+    onSubmit(passphrase || null);
+    onClose();
+  };
+
+  return (
+    <div className="modal-overlay" onClick={onClose}>
+      <div
+        className="modal-content passphrase-modal"
+        style={{ fontSize: `${baseFontSize}px` }}
+        onClick={(e) => e.stopPropagation()}
+      >
+        <div className="modal-header">
+          <h2>{isExport ? '북마크 내보내기' : '북마크 가져오기'}</h2>
+          <button className="modal-close" onClick={onClose}>✕</button>
+        </div>
+
+        <form
+          className="modal-body"
+          onSubmit={(e) => { e.preventDefault(); handleSubmit(); }}
+        >
+          <div className="compress-info">
+            {isExport
+              ? '저장된 비밀번호를 암호화할 암호를 입력하세요. 비워두면 비밀번호를 제외하고 내보냅니다.'
+              : '암호화된 북마크 파일입니다. 내보낼 때 사용한 암호를 입력하세요.'}
+          </div>
+
+          <div className="form-row">
+            <label>암호</label>
+            <input
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              autoFocus
+            />
+          </div>
+
+          {isExport && (
+            <div className="form-row">
+              <label>암호 확인</label>
+              <input
+                type="password"
+                value={confirmation}
+                onChange={(e) => setConfirmation(e.target.value)}
+              />
+            </div>
+          )}
+
+          {mismatch && confirmation.length > 0 && (
+            <div className="passphrase-mismatch">암호가 일치하지 않습니다</div>
+          )}
+        </form>
+
+        <div className="modal-footer">
+          <button onClick={onClose} className="btn-secondary">취소</button>
+          <button onClick={handleSubmit} className="btn-primary" disabled={!canSubmit}>
+            {isExport ? '내보내기' : '가져오기'}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -104,7 +104,7 @@ export function SettingsModal({ isOpen, onClose, scale }: SettingsModalProps) {
                 type="text"
                 value={editorPath}
                 onChange={(e) => { setEditorPath(e.target.value); setSaved(false); }}
-                placeholder="직접 입력: /Applications/에디터.app"
+                placeholder="직접 입력: /Applications/에디터.app 또는 code --wait {file}"
               />
             </div>
           </div>
@@ -9,6 +9,27 @@ export interface Connection {
   username: string;
   password: string;
   share?: string;
+  identityFile?: string;
+  proxyJump?: string;
+  folder?: string;
+  tags?: string[];
+  favorite?: boolean;
+  lastConnected?: string;
+  connectCount?: number;
+  initialPath?: string;
+  localDir?: string;
+  transferMode?: 'ascii' | 'binary';
+  ftpMode?: 'passive' | 'epsv' | 'active';
+  ftpNatWorkaround?: boolean;
+  charset?: string;
+  timeoutSecs?: number;
+  lastPath?: string;
+}
+
+export interface ConnectInfo {
+  initialPath: string;
+  fallbackPaths: string[];
+  localDir?: string;
 }
 
 export interface FileEntry {
@@ -28,3 +49,187 @@ export interface ConnectionState {
   loading: boolean;
   error: string | null;
 }
+
+export interface SkippedEntry {
+  name: string;
+  reason: string;
+}
+
+export interface ImportReport {
+  imported: Connection[];
+  skipped: SkippedEntry[];
+}
+
+export type OverwritePolicy = 'ask' | 'overwrite' | 'skip' | 'rename';
+
+export type Theme = 'system' | 'light' | 'dark';
+
+export interface Settings {
+  version: number;
+  editorPath: string;
+  defaultDownloadDir?: string;
+  transferConcurrency: number;
+  overwritePolicy: OverwritePolicy;
+  showHiddenFiles: boolean;
+  theme: Theme;
+  locale: string;
+  connectTimeoutSecs: number;
+  transferTimeoutSecs: number;
+  listingCacheTtlSecs: number;
+}
+
+export type SortKey = 'name' | 'size' | 'mtime' | 'type';
+
+export interface ListOptions {
+  sortBy?: SortKey;
+  descending?: boolean;
+  filter?: string;
+  regex?: boolean;
+  offset?: number;
+  limit?: number;
+}
+
+export interface ListPage {
+  entries: FileEntry[];
+  total: number;
+  offset: number;
+  hasMore: boolean;
+}
+
+export interface ListChunk {
+  requestId: string;
+  path: string;
+  entries: FileEntry[];
+  offset: number;
+  total: number;
+  sorted: boolean;
+  done: boolean;
+}
+
+export interface SearchOptions {
+  pattern?: string;
+  regex?: boolean;
+  minSize?: number;
+  maxSize?: number;
+  modifiedAfter?: string;
+  modifiedBefore?: string;
+  maxDepth?: number;
+  filesOnly?: boolean;
+  showHidden?: boolean;
+}
+
+export interface SearchResult {
+  searchId: string;
+  entries: FileEntry[];
+}
+
+export interface SearchSummary {
+  searchId: string;
+  matched: number;
+  scanned: number;
+  unreadable: number;
+  cancelled: boolean;
+}
+
+export interface CompressSummary {
+  unreadable: string[];
+  changed: string[];
+}
+
+export interface GrepOptions {
+  regex?: boolean;
+  ignoreCase?: boolean;
+  include?: string;
+  maxResults?: number;
+  showHidden?: boolean;
+}
+
+export interface GrepMatch {
+  path: string;
+  line: number;
+  text: string;
+}
+
+export interface GrepReport {
+  matches: GrepMatch[];
+  method: 'exec' | 'scan';
+  truncated: boolean;
+  skipped: string[];
+}
+
+export interface ExecOutputEvent {
+  execId: string;
+  stream: 'stdout' | 'stderr';
+  data: string;
+}
+
+export interface ExecResult {
+  exitStatus?: number;
+  cancelled: boolean;
+}
+
+export interface ShellOutputEvent {
+  shellId: string;
+  data: string;
+}
+
+export interface ShellExitEvent {
+  shellId: string;
+  exitStatus?: number;
+}
+
+export type EditStatus = 'uploaded' | 'conflict' | 'failed';
+
+export interface EditSyncEvent {
+  remotePath: string;
+  localPath: string;
+  status: EditStatus;
+  message?: string;
+}
+
+export interface EditedFileInfo {
+  remotePath: string;
+  localPath: string;
+  conflict: boolean;
+}
+
+export interface Thumbnail {
+  data: string;
+  mimeType: string;
+  width: number;
+  height: number;
+  originalWidth: number;
+  originalHeight: number;
+}
+
+export interface ArchiveEntry {
+  path: string;
+  size: number;
+  modified: string;
+  isDirectory: boolean;
+}
+
+export interface ArchiveListing {
+  entries: ArchiveEntry[];
+  truncated: boolean;
+}
+
+export type ExtractTarget = 'local' | 'remote';
+
+export interface ExtractSummary {
+  extracted: number;
+  skipped: number;
+  rejected: number;
+}
+
+export type LineEnding = 'lf' | 'crlf' | 'cr' | 'mixed' | 'none';
+
+export interface TextPreview {
+  text: string;
+  encoding: string;
+  hadErrors: boolean;
+  lineEnding: LineEnding;
+  offset: number;
+  totalSize: number;
+  truncated: boolean;
+}